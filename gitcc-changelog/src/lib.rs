@@ -34,6 +34,8 @@ pub struct Release {
     /// [Unreleased]: https://github.com/olivierlacan/keep-a-changelog/compare/v1.0.0...HEAD
     /// [1.0.0]: https://github.com/olivierlacan/keep-a-changelog/compare/v0.0.2...v0.0.1
     pub url: Option<String>,
+    /// Release notes (eg. the message of an annotated tag)
+    pub notes: Option<String>,
     /// Sections
     pub sections: Vec<Section>,
 }
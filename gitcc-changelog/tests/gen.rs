@@ -11,6 +11,7 @@ fn gen_changelog() {
                 version: "Unreleased".to_string(),
                 date: OffsetDateTime::now_utc(),
                 url: None,
+                notes: None,
                 sections: vec![
                     Section {
                         label: "New features".to_string(),
@@ -34,6 +35,7 @@ fn gen_changelog() {
                 version: "v0.0.1".to_string(),
                 date: datetime!(2021-01-01 13:00:55 UTC),
                 url: Some("https://github.com/gitcc/release/v0.0.1".to_string()),
+                notes: Some("First release".to_string()),
                 sections: vec![
                     Section {
                         label: "New features".to_string(),
@@ -57,7 +59,7 @@ fn gen_changelog() {
     };
 
     let changelog_str = changelog.render(TEMPLATE_CHANGELOG_STD).unwrap();
-    eprintln!("{changelog_str}");
+    assert!(changelog_str.contains("First release"));
 }
 
 #[test]
@@ -66,6 +68,7 @@ fn gen_release() {
         version: "v0.0.1".to_string(),
         date: datetime!(2021-01-01 13:00:55 UTC),
         url: Some("https://github.com/gitcc/release/v0.0.1".to_string()),
+        notes: Some("First release".to_string()),
         sections: vec![
             Section {
                 label: "New features".to_string(),
@@ -87,5 +90,5 @@ fn gen_release() {
     };
 
     let release_str = release.render(TEMPLATE_RELEASE_STD).unwrap();
-    eprintln!("{release_str}");
+    assert!(release_str.contains("First release"));
 }
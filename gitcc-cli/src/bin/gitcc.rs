@@ -13,7 +13,9 @@ fn main() {
     let res = match cli.commands {
         Commands::Init(args) => gitcc_cli::init::run(args),
         Commands::Config(args) => gitcc_cli::config::run(args),
+        Commands::Hooks(args) => gitcc_cli::hooks::run(args),
         Commands::Commit(args) => gitcc_cli::commit::run(args),
+        Commands::Lint(args) => gitcc_cli::lint::run(args),
         Commands::Log(args) => gitcc_cli::log::run(args),
         Commands::Version(args) => gitcc_cli::version::run(args),
         Commands::Changelog(args) => gitcc_cli::changelog::run(args),
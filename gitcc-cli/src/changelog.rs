@@ -4,15 +4,36 @@ use std::env;
 
 use clap::Parser;
 use gitcc_core::{
-    build_changelog, commit_history, ChangelogBuildOptions, Config, StatusShow,
-    TEMPLATE_CHANGELOG_STD,
+    build_changelog, commit_history, ChangelogBuildOptions, CommitHistoryOptions, Config,
+    StatusShow, TEMPLATE_CHANGELOG_STD,
 };
 
-use crate::{info, warn};
+use crate::{info, util::CommitOrder, warn};
 
 /// Changelog command arguments
 #[derive(Debug, Parser)]
 pub struct ChangelogArgs {
+    /// Start revision, excluded (eg. `v1.0.0`)
+    #[arg(long)]
+    pub from: Option<String>,
+    /// End revision, included (`HEAD` by default)
+    #[arg(long)]
+    pub to: Option<String>,
+    /// Only includes the commits since a tag (eg. `v1.0.0`)
+    #[arg(long, conflicts_with = "from")]
+    pub since_tag: Option<String>,
+    /// Only follows the 1st parent of merge commits
+    #[arg(long)]
+    pub first_parent: bool,
+    /// Order of the commits
+    #[arg(long, value_enum, default_value_t = CommitOrder::Default)]
+    pub order: CommitOrder,
+    /// Only includes the commits changing files in a path (directory, file, or glob)
+    #[arg(long = "path")]
+    pub paths: Vec<String>,
+    /// Package (monorepo)
+    #[arg(long)]
+    pub package: Option<String>,
     /// Includes all commits
     #[arg(long)]
     pub all: bool,
@@ -39,14 +60,30 @@ pub fn run(args: ChangelogArgs) -> anyhow::Result<()> {
     }
 
     // Generate the changelog
-    let history = commit_history(&cwd, &cfg)?;
+    let history = commit_history(
+        &cwd,
+        &cfg,
+        Some(CommitHistoryOptions {
+            package: args.package.clone(),
+            paths: args.paths.clone(),
+            range: match (args.from.as_ref().or(args.since_tag.as_ref()), &args.to) {
+                (Some(from), Some(to)) => Some(format!("{from}..{to}")),
+                (Some(from), None) => Some(format!("{from}..HEAD")),
+                (None, Some(to)) => Some(to.clone()),
+                (None, None) => None,
+            },
+            first_parent: args.first_parent,
+            order: args.order.into(),
+            ..Default::default()
+        }),
+    )?;
     let changelog_opts = ChangelogBuildOptions {
         origin_name: None,
         all: args.all,
         next_version: if args.unreleased {
             None
         } else {
-            Some(history.next_version_str())
+            history.next_version_str()
         },
     };
     let changelog = build_changelog(&cwd, &cfg, &history, Some(changelog_opts))?;
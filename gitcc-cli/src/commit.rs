@@ -31,6 +31,7 @@ pub fn run(_args: CommitArgs) -> anyhow::Result<()> {
         for (file, _) in status {
             eprintln!("\t{file}");
         }
+        #[allow(clippy::single_match)]
         match Confirm::with_theme(&ColorfulTheme::default())
             .with_prompt("continue ?")
             .report(true)
@@ -79,18 +80,18 @@ fn open_dialogue(config: &Config) -> anyhow::Result<ConvcoMessage> {
         commit_types_keys[i].clone()
     };
 
-    // > scope
-    let scope = {
-        let scope: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Commit scope")
+    // > scopes
+    let scopes = {
+        let scopes: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Commit scope(s), comma-separated")
             .report(true)
             .allow_empty(true)
             .interact_text()?;
-        if scope.is_empty() {
-            None
-        } else {
-            Some(scope.to_lowercase())
-        }
+        scopes
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
     };
 
     // > Short description
@@ -103,7 +104,7 @@ fn open_dialogue(config: &Config) -> anyhow::Result<ConvcoMessage> {
 
     let mut msg = ConvcoMessage {
         r#type,
-        scope,
+        scopes,
         is_breaking: false,
         desc,
         body: None,
@@ -148,6 +149,7 @@ fn open_dialogue(config: &Config) -> anyhow::Result<ConvcoMessage> {
 
     // > footer
     'footer_notes: loop {
+        #[allow(clippy::single_match)]
         match Confirm::with_theme(&ColorfulTheme::default())
             .with_prompt("Add footer note ?")
             .report(true)
@@ -0,0 +1,105 @@
+//! `hooks` command
+
+use std::{env, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use gitcc_core::{Config, HookInfo, HookStatus};
+
+use crate::{info, new_line, success, warn};
+
+/// `hooks` command arguments
+#[derive(Debug, Parser)]
+pub struct HooksArgs {
+    #[clap(subcommand)]
+    pub command: HooksCommand,
+}
+
+/// `hooks` subcommands
+#[derive(Debug, Subcommand)]
+pub enum HooksCommand {
+    /// Installs the git hooks (commit-msg, prepare-commit-msg)
+    Install,
+    /// Uninstalls the git hooks, and restores the chained hooks
+    Uninstall,
+    /// Shows the status of the git hooks
+    Status,
+    /// Runs a git hook (called by the installed hooks)
+    #[clap(hide = true)]
+    Run {
+        /// Hook name
+        hook: String,
+        /// Hook arguments
+        args: Vec<String>,
+    },
+}
+
+/// Executes the command `hooks`
+pub fn run(args: HooksArgs) -> anyhow::Result<()> {
+    let cwd = env::current_dir()?;
+    let bin = current_bin()?;
+
+    match args.command {
+        HooksCommand::Install => {
+            let infos = gitcc_core::install_hooks(&cwd, &bin)?;
+            success!("installed git hooks");
+            print_hooks(&infos);
+        }
+        HooksCommand::Uninstall => {
+            let infos = gitcc_core::uninstall_hooks(&cwd, &bin)?;
+            success!("uninstalled git hooks");
+            print_hooks(&infos);
+        }
+        HooksCommand::Status => {
+            let infos = gitcc_core::hooks_status(&cwd, &bin)?;
+            print_hooks(&infos);
+            if infos
+                .iter()
+                .any(|h| matches!(h.status, HookStatus::Stale { .. }))
+            {
+                new_line!();
+                warn!("some hooks are stale, update them with: gitcc hooks install");
+            }
+        }
+        HooksCommand::Run { hook, args } => match hook.as_str() {
+            "prepare-commit-msg" => {
+                let cfg = Config::load_from_fs(&cwd)?.unwrap_or_default();
+                let file = args
+                    .first()
+                    .ok_or_else(|| anyhow::anyhow!("missing commit message file"))?;
+                let source = args.get(1).map(|s| s.as_str()).filter(|s| !s.is_empty());
+                gitcc_core::prepare_commit_msg(&cfg, &PathBuf::from(file), source)?;
+            }
+            _ => anyhow::bail!("unsupported hook '{hook}'"),
+        },
+    }
+
+    Ok(())
+}
+
+/// Returns the path to the current gitcc binary
+pub(crate) fn current_bin() -> anyhow::Result<PathBuf> {
+    let bin = env::current_exe()?;
+    Ok(bin.canonicalize().unwrap_or(bin))
+}
+
+/// Prints the status of the hooks
+pub(crate) fn print_hooks(infos: &[HookInfo]) {
+    for hook in infos {
+        let status = match &hook.status {
+            HookStatus::Installed => hook.status.to_string().green(),
+            HookStatus::Stale { .. } => hook.status.to_string().yellow(),
+            HookStatus::NotInstalled | HookStatus::Foreign => hook.status.to_string().normal(),
+        };
+        info!(format!(
+            "{}: {}{}",
+            hook.name.bold(),
+            status,
+            if hook.chains {
+                " (chains existing hook)"
+            } else {
+                ""
+            }
+        ));
+    }
+}
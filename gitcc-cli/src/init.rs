@@ -7,20 +7,30 @@ use clap::Parser;
 use dialoguer::{theme::ColorfulTheme, Confirm};
 use gitcc_core::Config;
 
-use crate::{error, info, new_line, success, warn};
+use crate::{
+    error,
+    hooks::{current_bin, print_hooks},
+    info, new_line, success, warn,
+};
 
 /// Init command arguments
 #[derive(Debug, Parser)]
-pub struct InitArgs {}
+pub struct InitArgs {
+    /// Installs the git hooks (commit-msg, prepare-commit-msg)
+    #[arg(long)]
+    pub hooks: bool,
+}
 
 /// Executes the commnad `init`
-pub fn run(_args: InitArgs) -> anyhow::Result<()> {
+pub fn run(args: InitArgs) -> anyhow::Result<()> {
     let cwd = env::current_dir()?;
     let config = Config::load_from_fs(&cwd)?;
 
     new_line!();
+    let mut create_config = true;
     if config.is_some() {
         warn!("repo already has a config");
+        #[allow(clippy::single_match)]
         match Confirm::with_theme(&ColorfulTheme::default())
             .with_prompt("overwrite ?")
             .report(true)
@@ -29,7 +39,7 @@ pub fn run(_args: InitArgs) -> anyhow::Result<()> {
         {
             false => {
                 error!("config not recreated");
-                return Ok(());
+                create_config = false;
             }
             true => {}
         }
@@ -37,8 +47,16 @@ pub fn run(_args: InitArgs) -> anyhow::Result<()> {
         info!("config not found");
     }
 
-    let config = Config::default();
-    config.save_to_fs(&cwd, true)?;
-    success!("created default config");
+    if create_config {
+        let config = Config::default();
+        config.save_to_fs(&cwd, true)?;
+        success!("created default config");
+    }
+
+    if args.hooks {
+        let infos = gitcc_core::install_hooks(&cwd, &current_bin()?)?;
+        success!("installed git hooks");
+        print_hooks(&infos);
+    }
     Ok(())
 }
@@ -5,7 +5,9 @@ use clap::{Parser, Subcommand};
 pub mod changelog;
 pub mod commit;
 pub mod config;
+pub mod hooks;
 pub mod init;
+pub mod lint;
 pub mod log;
 pub mod release;
 mod util;
@@ -28,8 +30,12 @@ pub enum Commands {
     Init(init::InitArgs),
     /// Shows the current configuration
     Config(config::ConfigArgs),
+    /// Manages the git hooks
+    Hooks(hooks::HooksArgs),
     /// Creates a conventional commit
     Commit(commit::CommitArgs),
+    /// Checks that commit messages follow the conventional commits format
+    Lint(lint::LintArgs),
     /// Displays the commit history
     Log(log::LogArgs),
     /// Checks the current version and determines the next version
@@ -45,7 +51,9 @@ pub fn exec(cli: Cli) -> anyhow::Result<()> {
     match cli.commands {
         Commands::Init(args) => init::run(args),
         Commands::Config(args) => config::run(args),
+        Commands::Hooks(args) => hooks::run(args),
         Commands::Commit(args) => commit::run(args),
+        Commands::Lint(args) => lint::run(args),
         Commands::Version(args) => version::run(args),
         Commands::Log(args) => log::run(args),
         Commands::Changelog(args) => changelog::run(args),
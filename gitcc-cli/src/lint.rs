@@ -0,0 +1,91 @@
+//! `lint` command
+
+use std::{
+    env, fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use anyhow::bail;
+use clap::Parser;
+use colored::Colorize;
+use gitcc_core::{Config, LintReport, Severity};
+
+use crate::{error, info, success, warn};
+
+/// `lint` command arguments
+#[derive(Debug, Parser)]
+pub struct LintArgs {
+    /// Commit message file (eg. the file passed to the `commit-msg` hook), or `-` to read from stdin
+    #[arg(conflicts_with = "range")]
+    pub file: Option<PathBuf>,
+    /// Lints the commits of a revision range (eg. `main..HEAD`)
+    #[arg(long)]
+    pub range: Option<String>,
+}
+
+/// Executes the command `lint`
+pub fn run(args: LintArgs) -> anyhow::Result<()> {
+    let cwd = env::current_dir()?;
+    let cfg = Config::load_from_fs(&cwd)?;
+    let cfg = if let Some(cfg) = cfg {
+        cfg
+    } else {
+        info!("using default config");
+        Config::default()
+    };
+
+    let reports = match (&args.file, &args.range) {
+        (Some(file), None) => {
+            let message = if file.as_os_str() == "-" {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                buf
+            } else {
+                fs::read_to_string(file)?
+            };
+            vec![gitcc_core::lint_message(&cfg, &message)]
+        }
+        (None, Some(range)) => gitcc_core::lint_commits(&cwd, &cfg, range)?,
+        _ => bail!("a commit message file, '-' (stdin), or a revision range (--range) is required"),
+    };
+
+    let nb_invalid = reports.iter().filter(|r| !r.is_valid()).count();
+    for report in reports.iter().filter(|r| !r.issues.is_empty()) {
+        print_report(report);
+    }
+
+    if nb_invalid > 0 {
+        bail!("{nb_invalid} commit message(s) do not follow the conventional commits format");
+    }
+    success!(format!("{} commit message(s) checked", reports.len()));
+    Ok(())
+}
+
+/// Prints the issues of a message
+fn print_report(report: &LintReport) {
+    let subject = report.message.lines().next().unwrap_or_default();
+    let subject = match &report.commit_id {
+        Some(id) => format!("{} {}", id[..7].yellow(), subject.bold()),
+        None => subject.bold().to_string(),
+    };
+    if report.is_valid() {
+        warn!(subject);
+    } else {
+        error!(subject);
+    }
+    if let Some(err) = &report.parse_error {
+        for line in err.render(&report.message).lines() {
+            eprintln!("  {line}");
+        }
+    } else {
+        for issue in &report.issues {
+            let severity = match issue.severity {
+                Severity::Error => issue.severity.to_string().red(),
+                _ => issue.severity.to_string().yellow(),
+            };
+            eprintln!("  {severity}: {issue}");
+        }
+    }
+    eprintln!();
+}
@@ -4,16 +4,41 @@ use std::env;
 
 use clap::Parser;
 use colored::Colorize;
-use gitcc_core::Config;
+use gitcc_core::{CommitHistoryOptions, CommitKind, Config};
 
-use crate::{info, new_line};
+use crate::{
+    info, new_line,
+    util::{print_json_items, CommitOrder, OutputFormat},
+};
 
 /// `log` command arguments
 #[derive(Debug, Parser)]
-pub struct LogArgs {}
+pub struct LogArgs {
+    /// Revision range (eg. `v1.0.0..HEAD`, or `HEAD~10`)
+    #[arg(long)]
+    pub range: Option<String>,
+    /// Only includes the commits since a tag (eg. `v1.0.0`)
+    #[arg(long, conflicts_with = "range")]
+    pub since_tag: Option<String>,
+    /// Only follows the 1st parent of merge commits
+    #[arg(long)]
+    pub first_parent: bool,
+    /// Order of the commits
+    #[arg(long, value_enum, default_value_t = CommitOrder::Default)]
+    pub order: CommitOrder,
+    /// Only includes the commits changing files in a path (directory, file, or glob)
+    #[arg(long = "path")]
+    pub paths: Vec<String>,
+    /// Package (monorepo)
+    #[arg(long)]
+    pub package: Option<String>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
 
 /// Executes the commnad `init`
-pub fn run(_args: LogArgs) -> anyhow::Result<()> {
+pub fn run(args: LogArgs) -> anyhow::Result<()> {
     new_line!();
 
     let cwd = env::current_dir()?;
@@ -25,12 +50,37 @@ pub fn run(_args: LogArgs) -> anyhow::Result<()> {
         Config::default()
     };
 
-    let history = gitcc_core::commit_history(&cwd, &config)?;
+    let history = gitcc_core::commit_history(
+        &cwd,
+        &config,
+        Some(CommitHistoryOptions {
+            package: args.package.clone(),
+            paths: args.paths.clone(),
+            range: match &args.since_tag {
+                Some(tag) => Some(format!("{tag}..HEAD")),
+                None => args.range.clone(),
+            },
+            first_parent: args.first_parent,
+            order: args.order.into(),
+            ..Default::default()
+        }),
+    )?;
+    if args.format != OutputFormat::Text {
+        return print_json_items(args.format, &history.commits);
+    }
     for c in history.commits.iter().rev() {
         println!("{}{}", "commit: ".blue().bold(), c.id.to_string().bold());
-        if let Some(tag) = &c.tag {
+        for tag in &c.tags {
             println!("{}{}", "tag: ".magenta(), tag.name.bold());
         }
+        if c.kind != CommitKind::Regular {
+            println!(
+                "{}{}{}",
+                "kind: ".cyan(),
+                c.kind,
+                if c.ignored { " (ignored)" } else { "" }
+            );
+        }
         println!("{}{}", "date: ".cyan(), c.date);
         println!(
             "{}{} <{}>",
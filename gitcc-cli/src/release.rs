@@ -1,15 +1,38 @@
 //! `release` command
 
-use std::{env, fs, process::exit};
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process::exit,
+    time::Duration,
+};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use dialoguer::{theme::ColorfulTheme, Confirm};
-use gitcc_core::{ChangelogBuildOptions, Config, StatusShow, TEMPLATE_CHANGELOG_STD};
+use gitcc_core::{
+    semver::Version, ChangelogBuildOptions, CommitHistoryOptions, Config, ReleaseEnv, ReleasePlan,
+    ReleaseStage, ReleaseTransaction, StatusShow, VersionIncr, ZeroMajorPolicy,
+    TEMPLATE_CHANGELOG_STD,
+};
 
 use crate::{error, info, success, warn};
 
+/// Exit codes of the `release` command (other errors exit with 1)
+const EXIT_CODES_HELP: &str = "Exit codes:
+  2  usage error (eg. a prompt is required, but stdin is not a terminal)
+  3  release aborted at a prompt
+  4  repo is dirty (--ci)
+  5  invalid next version (not greater than the current version, or already released)
+  6  nothing to release (no unreleased commits)
+  7  failed to generate the changelog or to bump the version files
+  8  release failed, and was rolled back
+  9  release failed, and was not rolled back
+  10 release created, but the push or a post_push hook failed (--push)";
+
 /// Commit command arguments
 #[derive(Debug, Parser)]
+#[command(after_help = EXIT_CODES_HELP, disable_version_flag = true)]
 pub struct ReleaseArgs {
     /// Dry run mode
     #[arg(long)]
@@ -17,6 +40,88 @@ pub struct ReleaseArgs {
     /// Sets the next version to be v1.0.0
     #[arg(long)]
     pub v1: bool,
+    /// Package (monorepo)
+    #[arg(long)]
+    pub package: Option<String>,
+    /// Creates a prerelease on a channel (eg. alpha, beta, rc)
+    #[arg(long, conflicts_with = "v1")]
+    pub pre: Option<String>,
+    /// Sets the next version (eg. 1.2.0)
+    #[arg(long, conflicts_with_all = ["v1", "pre", "increment"])]
+    pub version: Option<Version>,
+    /// Sets the version increment, instead of deriving it from the commits
+    #[arg(long, value_enum, conflicts_with = "v1")]
+    pub increment: Option<Increment>,
+    /// Pushes the release commit and tag, and runs the post_push hooks
+    #[arg(long)]
+    pub push: bool,
+    /// Remote to push the release to
+    #[arg(long, default_value = "origin", requires = "push")]
+    pub remote: String,
+    /// Does not roll back the release on failure (for debugging)
+    #[arg(long)]
+    pub no_rollback: bool,
+    /// Answers yes to the prompts
+    #[arg(long, short)]
+    pub yes: bool,
+    /// Non-interactive mode for CI (implies --yes, and fails if the repo is dirty)
+    #[arg(long)]
+    pub ci: bool,
+}
+
+/// Version increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Increment {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl From<Increment> for VersionIncr {
+    fn from(value: Increment) -> Self {
+        match value {
+            Increment::Major => VersionIncr::Major,
+            Increment::Minor => VersionIncr::Minor,
+            Increment::Patch => VersionIncr::Patch,
+        }
+    }
+}
+
+/// Release failure, with its exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReleaseExit {
+    Usage = 2,
+    Aborted = 3,
+    DirtyRepo = 4,
+    InvalidVersion = 5,
+    NothingToRelease = 6,
+    PrepareFailed = 7,
+    RolledBack = 8,
+    PartialRelease = 9,
+    PushFailed = 10,
+}
+
+impl ReleaseExit {
+    /// Exits with the error code
+    fn exit(self) -> ! {
+        exit(self as i32)
+    }
+}
+
+/// Asks for a confirmation, unless the prompts are disabled
+fn confirm(args: &ReleaseArgs) -> anyhow::Result<()> {
+    if args.yes || args.ci {
+        return Ok(());
+    }
+    match Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("continue ?")
+        .report(true)
+        .default(false)
+        .interact()?
+    {
+        true => Ok(()),
+        false => ReleaseExit::Aborted.exit(),
+    }
 }
 
 /// Executes the command `release`
@@ -31,45 +136,94 @@ pub fn run(args: ReleaseArgs) -> anyhow::Result<()> {
         Config::default()
     };
 
+    // the prompts require a terminal
+    let is_prompt = !args.yes && !args.ci;
+    if is_prompt && !io::stdin().is_terminal() {
+        error!("stdin is not a terminal, use --yes or --ci to release without prompts");
+        ReleaseExit::Usage.exit();
+    }
+
     // make sure there is no untracked/uncommitted changes
     let dirty_files = gitcc_core::git_status(&cwd, StatusShow::IndexAndWorkdir)?;
     if !dirty_files.is_empty() {
-        warn!("repo is dirty");
-        match Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("continue ?")
-            .report(true)
-            .default(false)
-            .interact()?
-        {
-            true => {}
-            false => {
-                exit(1);
-            }
+        if args.ci {
+            error!("repo is dirty");
+            ReleaseExit::DirtyRepo.exit();
         }
+        warn!("repo is dirty");
+        confirm(&args)?;
     }
 
     // find the next version
-    let commit_history = gitcc_core::commit_history(&cwd, &cfg)?;
-    let mut next_version = commit_history.next_version_str();
+    let commit_history = gitcc_core::commit_history(
+        &cwd,
+        &cfg,
+        Some(CommitHistoryOptions {
+            package: args.package.clone(),
+            ..Default::default()
+        }),
+    )?;
+    let is_unreleased = commit_history
+        .commits
+        .iter()
+        .any(|c| c.version_tag.is_none() && !c.ignored);
+    let is_override = args.version.is_some() || args.increment.is_some() || args.v1;
+    if !is_unreleased && !is_override {
+        error!("nothing to release");
+        ReleaseExit::NothingToRelease.exit();
+    }
+    let mut next_version = match args.increment {
+        // NB: an explicit increment is applied as is, even for 0.x versions
+        Some(incr) => Some(
+            VersionIncr::from(incr).apply(&commit_history.stable_version, ZeroMajorPolicy::Strict),
+        ),
+        None => commit_history.next_version.clone(),
+    };
+    if let (Some(channel), Some(version)) = (&args.pre, &next_version) {
+        next_version = Some(commit_history.prerelease_version(version, channel)?);
+    }
     if args.v1 {
         warn!(format!("forcing 1st stable release"));
-        next_version = "v1.0.0".to_string();
+        next_version = Some(Version::new(1, 0, 0));
+    }
+    if let Some(version) = &args.version {
+        next_version = Some(version.clone());
+    }
+    let Some(next_version) = next_version else {
+        error!("nothing to release");
+        ReleaseExit::NothingToRelease.exit();
+    };
+    if commit_history.versions.contains(&next_version) {
+        error!(format!("version {next_version} is already released"));
+        ReleaseExit::InvalidVersion.exit();
     }
-    info!(format!("next version: {}", next_version));
+    if let Some(curr_version) = &commit_history.curr_version {
+        if next_version <= *curr_version {
+            error!(format!(
+                "version {next_version} is not greater than the current version {curr_version}"
+            ));
+            ReleaseExit::InvalidVersion.exit();
+        }
+    }
+    let next_tag = commit_history.format_tag(&next_version);
+    info!(format!("next version: {}", next_tag));
+    let stable_version = commit_history
+        .stable_version
+        .clone()
+        .unwrap_or(Version::new(0, 0, 0));
+    let release_env = ReleaseEnv {
+        version: next_version.clone(),
+        tag: next_tag.clone(),
+        prev_version: commit_history.curr_version.clone(),
+        increment: VersionIncr::between(&stable_version, &next_version),
+    };
+    let root_dir = gitcc_core::get_root_dir(&cwd).expect("not a git repo");
 
     // before continuing, leave an escape hatch to set the version manually,
     // or do other checks/tests manually.
-    info!("before committing, bump the packages manually, run tests, etc...");
-    match Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("continue ?")
-        .report(true)
-        .default(false)
-        .interact()?
-    {
-        true => {}
-        false => {
-            exit(1);
-        }
+    if is_prompt {
+        info!("before committing, bump the packages manually, run tests, etc...");
+        confirm(&args)?;
     }
 
     // build the changelog
@@ -80,90 +234,152 @@ pub fn run(args: ReleaseArgs) -> anyhow::Result<()> {
         Some(ChangelogBuildOptions {
             origin_name: None,
             all: false,
-            next_version: Some(next_version.clone()),
+            next_version: Some(next_tag.clone()),
         }),
     )?;
     let changelog_str = match changelog.render(TEMPLATE_CHANGELOG_STD) {
         Ok(s) => s,
         Err(err) => {
             error!(format!("failed to generate the changelog: {err}"));
-            exit(1);
+            ReleaseExit::PrepareFailed.exit();
         }
     };
-    if !args.dry_run {
-        let root_dir = gitcc_core::get_root_dir(&cwd).expect("not a git repo");
-        match fs::write(root_dir.join("CHANGELOG.md"), changelog_str) {
-            Ok(_ok) => {
-                success!("changelog written to file")
-            }
-            Err(err) => {
-                error!(format!("failed to write the changelog: {err}"));
-                exit(1);
-            }
-        }
-    } else {
-        info!("(dry-run) changelog not written to file")
-    }
+    let changelog_path = match &args.package {
+        Some(name) => cfg.package(name)?.changelog_path(),
+        None => PathBuf::from("CHANGELOG.md"),
+    };
 
-    // bump the packages versions
-    if !args.dry_run {
-        for cmd in cfg.release.bump_cmds {
-            match gitcc_core::exec_bump_command(&cmd, &next_version) {
-                Ok(_ok) => {
-                    success!(format!("executed bump command: {cmd}"));
-                }
-                Err(err) => {
-                    error!(format!("failed to bump packages: {err}"));
-                    exit(1);
-                }
-            }
+    // bump the version files
+    let bump_files = match &args.package {
+        Some(name) => &cfg.package(name)?.bump_files,
+        None => &cfg.release.bump_files,
+    };
+    let bumps = match gitcc_core::bump_files(&root_dir, bump_files, &next_version.to_string()) {
+        Ok(bumps) => bumps,
+        Err(err) => {
+            error!(format!("failed to bump the version files: {err}"));
+            ReleaseExit::PrepareFailed.exit();
         }
-    } else {
-        info!("(dry-run) skipped executing bump commands")
+    };
+
+    let plan = ReleasePlan {
+        env: release_env,
+        changelog_path,
+        changelog: changelog_str,
+        bumps,
+        bump_cmds: cfg.release.bump_cmds.clone(),
+        hooks: cfg.release.hooks.clone(),
+        timeout: Duration::from_secs(cfg.release.timeout),
+        commit_message: format!("chore(release): Release {next_tag}"),
+        tag_message: format!("Release {next_tag}"),
+    };
+    if args.dry_run {
+        print_dry_run(&plan, args.push.then_some(args.remote.as_str()));
+        return Ok(());
     }
 
-    // commit the changes
-    if !args.dry_run {
-        match gitcc_core::add_all_changes(&cwd) {
-            Ok(_ok) => {}
-            Err(err) => {
-                error!(format!("failed to add the changes: {err}"));
-                exit(1);
+    // execute the release, and roll it back on failure
+    let mut tx = ReleaseTransaction::begin(&root_dir)?;
+    if let Err(err) = tx.execute(&plan, |step| success!(step)) {
+        error!(format!("release failed: {err}"));
+        if args.no_rollback {
+            warn!("rollback skipped, revert these steps manually:");
+            for step in tx.steps() {
+                warn!(format!("  {step}"));
             }
+            ReleaseExit::PartialRelease.exit();
         }
-        match gitcc_core::commit_changes(&cwd, &format!("chore(release): Release {next_version}")) {
-            Ok(_commit) => {
-                success!("commited changes");
+        match tx.rollback() {
+            Ok(_ok) => {
+                warn!("release rolled back");
+                ReleaseExit::RolledBack.exit();
             }
             Err(err) => {
-                error!(format!("failed to commit: {err}"));
-                exit(1);
+                error!(format!("failed to roll back the release: {err}"));
+                ReleaseExit::PartialRelease.exit();
             }
         }
-    } else {
-        info!("(dry-run) changes not committed");
     }
 
-    // tagging the commit
-    if !args.dry_run {
-        match gitcc_core::set_annotated_tag(&cwd, &next_version, &format!("Release {next_version}"))
-        {
-            Ok(_ok) => {
-                success!(format!("tag {} added", next_version));
+    // push the release (it is not rolled back once created locally)
+    if args.push {
+        // NB: the progress line is only drawn on a terminal (not in the CI logs)
+        let is_tty = io::stderr().is_terminal();
+        let clear_line = || {
+            if is_tty {
+                eprint!("\r\x1b[K");
             }
-            Err(err) => {
-                error!(format!("failed to add the changes: {err}"));
-                exit(1);
+        };
+        let push_res = tx.push(
+            &plan,
+            &args.remote,
+            |step| {
+                clear_line();
+                success!(step)
+            },
+            |p| {
+                if is_tty {
+                    eprint!(
+                        "\r\x1b[Kpushing objects: {}/{} ({} bytes)",
+                        p.current, p.total, p.bytes
+                    )
+                }
+            },
+        );
+        if let Err(err) = push_res {
+            clear_line();
+            error!(format!("{err}"));
+            if !tx.is_pushed() {
+                warn!(format!(
+                    "=> Push the changes with: git push --follow-tags {}",
+                    args.remote
+                ));
             }
+            ReleaseExit::PushFailed.exit();
         }
     } else {
-        info!(format!("(dry-run) tag '{}' not set", next_version));
+        if !plan.hooks.post_push.is_empty() {
+            warn!("post_push hooks skipped, the changes are not pushed (--push)");
+        }
+        warn!("=> Push the changes with: git push --follow-tags");
     }
 
     // Other steps
-    warn!("=> Push the changes with: git push --follow-tags");
     warn!("=> Create the github release");
     warn!("=> Publish the updated packages (crates.io, npm, brew, etc...)");
 
     Ok(())
 }
+
+/// Prints the release steps in dry-run mode
+fn print_dry_run(plan: &ReleasePlan, remote: Option<&str>) {
+    let print_hooks = |stage: ReleaseStage| {
+        for cmd in plan.hooks.commands(stage) {
+            info!(format!("(dry-run) skipped {stage} hook: {cmd}"));
+        }
+    };
+    print_hooks(ReleaseStage::PreRelease);
+    info!(format!(
+        "(dry-run) changelog {} not written",
+        plan.changelog_path.display()
+    ));
+    print_hooks(ReleaseStage::PostChangelog);
+    for bump in plan.bumps.iter().filter(|b| b.is_changed()) {
+        info!(format!(
+            "(dry-run) version file {} not written:",
+            bump.path.display()
+        ));
+        eprint!("{}", bump.diff());
+    }
+    for cmd in &plan.bump_cmds {
+        info!(format!("(dry-run) skipped bump command: {cmd}"));
+    }
+    print_hooks(ReleaseStage::PreCommit);
+    info!("(dry-run) changes not committed");
+    info!(format!("(dry-run) tag '{}' not set", plan.env.tag));
+    print_hooks(ReleaseStage::PostTag);
+    if let Some(remote) = remote {
+        info!(format!("(dry-run) release not pushed to {remote}"));
+        print_hooks(ReleaseStage::PostPush);
+    }
+}
@@ -1,5 +1,70 @@
 //! Misc. utilities
 
+use clap::ValueEnum;
+use gitcc_core::LogOrder;
+use serde::Serialize;
+
+/// Output format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable output
+    #[default]
+    Text,
+    /// JSON document
+    Json,
+    /// Newline-delimited JSON (1 item per line)
+    Ndjson,
+}
+
+/// Order of the commits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CommitOrder {
+    /// Default git order
+    #[default]
+    Default,
+    /// Topological order (a commit is never listed before its children)
+    Topo,
+    /// Commit time order
+    Time,
+}
+
+impl From<CommitOrder> for LogOrder {
+    fn from(value: CommitOrder) -> Self {
+        match value {
+            CommitOrder::Default => LogOrder::Default,
+            CommitOrder::Topo => LogOrder::Topological,
+            CommitOrder::Time => LogOrder::Time,
+        }
+    }
+}
+
+/// Prints a value to stdout as JSON
+///
+/// With the `ndjson` format, the value is printed on a single line.
+pub fn print_json<T: Serialize>(format: OutputFormat, value: &T) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        _ => println!("{}", serde_json::to_string(value)?),
+    }
+    Ok(())
+}
+
+/// Prints items to stdout as JSON
+///
+/// With the `json` format, the items are printed as a JSON array,
+/// and with the `ndjson` format, each item is printed on its own line.
+pub fn print_json_items<T: Serialize>(format: OutputFormat, items: &[T]) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(items)?),
+        _ => {
+            for item in items {
+                println!("{}", serde_json::to_string(item)?);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Prints a new line to stderr
 #[macro_export]
 macro_rules! new_line {
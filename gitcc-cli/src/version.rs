@@ -3,16 +3,49 @@
 use std::env;
 
 use clap::Parser;
-use gitcc_core::{commit_history, Config, StatusShow};
+use gitcc_core::{
+    commit_history, semver::Version, Commit, CommitHistoryOptions, Config, IgnoredTag, StatusShow,
+};
+use serde::Serialize;
 
-use crate::{info, new_line, warn};
+use crate::{
+    info, new_line,
+    util::{print_json, OutputFormat},
+    warn,
+};
 
 /// Bump command arguments
 #[derive(Debug, Parser)]
-pub struct VersionArgs {}
+pub struct VersionArgs {
+    /// Only includes the commits changing files in a path (directory, file, or glob)
+    #[arg(long = "path")]
+    pub paths: Vec<String>,
+    /// Package (monorepo)
+    #[arg(long)]
+    pub package: Option<String>,
+    /// Prerelease channel (eg. alpha, beta, rc)
+    #[arg(long)]
+    pub pre: Option<String>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// Version info (JSON output)
+#[derive(Debug, Serialize)]
+struct VersionInfo<'a> {
+    /// Current version
+    curr_version: Option<&'a Version>,
+    /// Next version (None if there is nothing to release)
+    next_version: Option<Version>,
+    /// Unreleased commits
+    unreleased_commits: Vec<&'a Commit>,
+    /// Tags which look like versions, but are ignored
+    ignored_tags: &'a [IgnoredTag],
+}
 
 /// Gets the current version and determines the next version
-pub fn run(_args: VersionArgs) -> anyhow::Result<()> {
+pub fn run(args: VersionArgs) -> anyhow::Result<()> {
     new_line!();
 
     // load the config
@@ -31,14 +64,45 @@ pub fn run(_args: VersionArgs) -> anyhow::Result<()> {
         warn!("repo is dirty");
     }
 
-    let history = commit_history(&cwd, &config)?;
+    let history = commit_history(
+        &cwd,
+        &config,
+        Some(CommitHistoryOptions {
+            package: args.package.clone(),
+            paths: args.paths.clone(),
+            since_last_release: true,
+            ..Default::default()
+        }),
+    )?;
+    let next_version = match &args.pre {
+        Some(channel) => history.next_prerelease_version(channel)?,
+        None => history.next_version.clone(),
+    };
+    if args.format != OutputFormat::Text {
+        let info = VersionInfo {
+            curr_version: history.curr_version.as_ref(),
+            next_version,
+            unreleased_commits: history
+                .commits
+                .iter()
+                .filter(|c| c.version_tag.is_none())
+                .collect(),
+            ignored_tags: &history.ignored_tags,
+        };
+        return print_json(args.format, &info);
+    }
+    for tag in &history.ignored_tags {
+        warn!(format!("ignoring tag '{}': {}", tag.name, tag.reason));
+    }
     println!(
         "{} --> {}",
         history
             .curr_version
             .map(|v| v.to_string())
             .unwrap_or_else(|| "none".to_string()),
-        history.next_version
+        next_version
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "none (nothing to release)".to_string())
     );
 
     Ok(())
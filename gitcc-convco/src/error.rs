@@ -0,0 +1,148 @@
+//! Error
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Conventional commit error
+///
+/// The error locates the offending token in the message, and may suggest a fix.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
+#[error("invalid conventional commit: {kind} (line {}, column {})", .span.line, .span.column)]
+pub struct ConvcoError {
+    /// Kind of error
+    pub kind: ConvcoErrorKind,
+    /// Location of the offending token
+    pub span: Span,
+    /// Offending token (empty if the token is missing, eg. at the end of a line)
+    pub token: String,
+    /// Suggested fix
+    pub suggestion: Option<String>,
+}
+
+/// Kind of conventional commit error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConvcoErrorKind {
+    /// The message is empty
+    EmptyMessage,
+    /// The subject does not start with a type
+    MissingType,
+    /// The type contains an invalid character
+    InvalidType,
+    /// The scope is empty, unclosed, or contains an invalid character
+    InvalidScope,
+    /// The `: ` separator after the type and scope is missing
+    MissingSeparator,
+    /// The description is empty
+    MissingDescription,
+    /// The subject does not follow the format `<type>[(<scope>)][!]: <description>`
+    InvalidSubject,
+    /// The body is not separated from the subject by a blank line
+    MissingBlankLine,
+    /// A footer token contains a white space
+    InvalidFooterToken,
+    /// A footer line is not a `<token>: <value>` pair
+    InvalidFooterLine,
+    /// The type is not lowercase (lenient parsing only)
+    TypeCase,
+    /// The type is not a known type (lenient parsing only)
+    UnknownType,
+    /// The description starts with an uppercase letter (lenient parsing only)
+    DescriptionCase,
+}
+
+impl Display for ConvcoErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvcoErrorKind::EmptyMessage => write!(f, "empty message"),
+            ConvcoErrorKind::MissingType => write!(f, "missing type"),
+            ConvcoErrorKind::InvalidType => write!(f, "invalid character in type"),
+            ConvcoErrorKind::InvalidScope => write!(f, "invalid scope"),
+            ConvcoErrorKind::MissingSeparator => write!(f, "missing ': ' separator"),
+            ConvcoErrorKind::MissingDescription => write!(f, "missing description"),
+            ConvcoErrorKind::InvalidSubject => write!(f, "invalid subject line"),
+            ConvcoErrorKind::MissingBlankLine => {
+                write!(f, "body must be separated by an empty line")
+            }
+            ConvcoErrorKind::InvalidFooterToken => write!(f, "invalid footer token"),
+            ConvcoErrorKind::InvalidFooterLine => write!(f, "invalid footer line"),
+            ConvcoErrorKind::TypeCase => write!(f, "type is not lowercase"),
+            ConvcoErrorKind::UnknownType => write!(f, "unknown type"),
+            ConvcoErrorKind::DescriptionCase => {
+                write!(f, "description starts with an uppercase letter")
+            }
+        }
+    }
+}
+
+/// Location in a message
+///
+/// Lines and columns start at 1, and are counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Line
+    pub line: usize,
+    /// Column
+    pub column: usize,
+    /// Length (in characters)
+    pub len: usize,
+}
+
+impl Span {
+    /// Creates a new span
+    pub fn new(line: usize, column: usize, len: usize) -> Self {
+        Self { line, column, len }
+    }
+}
+
+impl ConvcoError {
+    /// Creates a new error
+    pub(crate) fn new(kind: ConvcoErrorKind, span: Span, token: &str) -> Self {
+        Self {
+            kind,
+            span,
+            token: token.to_string(),
+            suggestion: None,
+        }
+    }
+
+    /// Sets the suggested fix
+    pub(crate) fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Renders the error with an excerpt of the message
+    ///
+    /// The offending token is underlined with carets, eg.
+    ///
+    /// ```text
+    /// error: missing ': ' separator (line 1, column 5)
+    ///   |
+    /// 1 | feat add a feature
+    ///   |     ^
+    ///   = help: feat: add a feature
+    /// ```
+    pub fn render(&self, message: &str) -> String {
+        let line = message.lines().nth(self.span.line - 1).unwrap_or_default();
+        let line_no = self.span.line.to_string();
+        let margin = " ".repeat(line_no.len());
+
+        let mut s = format!(
+            "error: {} (line {}, column {})\n",
+            self.kind, self.span.line, self.span.column
+        );
+        s.push_str(&format!("{margin} |\n"));
+        s.push_str(&format!("{line_no} | {line}\n"));
+        s.push_str(&format!(
+            "{margin} | {}{}",
+            " ".repeat(self.span.column - 1),
+            "^".repeat(self.span.len.max(1))
+        ));
+        if let Some(suggestion) = &self.suggestion {
+            s.push_str(&format!("\n{margin} = help: {suggestion}"));
+        }
+        s
+    }
+}
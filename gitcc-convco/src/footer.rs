@@ -0,0 +1,167 @@
+//! Footer
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Separator between a trailer token and its value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Separator {
+    /// `<token>: <value>` (eg. `Refs: #123`)
+    #[default]
+    Colon,
+    /// `<token> #<value>` (eg. `Closes #123`)
+    Hash,
+}
+
+impl Display for Separator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Separator::Colon => write!(f, ": "),
+            Separator::Hash => write!(f, " #"),
+        }
+    }
+}
+
+/// Footer trailer (eg. `Refs: #123` or `Closes #123`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trailer {
+    /// Token (eg. `Refs`)
+    pub key: String,
+    /// Separator
+    pub separator: Separator,
+    /// Value
+    ///
+    /// A value can span several lines, the continuation lines are kept as is.
+    /// With the `#` separator, the value does not include the `#` (eg. `123` for `Closes #123`).
+    pub value: String,
+}
+
+impl Trailer {
+    /// Creates a new trailer with the `: ` separator
+    pub fn new(key: &str, value: &str) -> Self {
+        Self::with_separator(key, Separator::Colon, value)
+    }
+
+    /// Creates a new trailer with a specific separator
+    pub fn with_separator(key: &str, separator: Separator, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            separator,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for Trailer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.key, self.separator, self.value)
+    }
+}
+
+/// Message footer
+///
+/// The footer is an ordered list of trailers, following the git trailers convention:
+/// a token can be repeated (eg. several `Co-authored-by`), and a value can span several lines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Footer {
+    /// Trailers
+    pub trailers: Vec<Trailer>,
+}
+
+impl Footer {
+    /// Creates an empty footer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a trailer
+    pub fn push(&mut self, key: &str, value: &str) -> &mut Self {
+        self.trailers.push(Trailer::new(key, value));
+        self
+    }
+
+    /// Adds a trailer with a specific separator
+    pub fn push_with_separator(
+        &mut self,
+        key: &str,
+        separator: Separator,
+        value: &str,
+    ) -> &mut Self {
+        self.trailers
+            .push(Trailer::with_separator(key, separator, value));
+        self
+    }
+
+    /// Checks if the footer has a trailer with a specific token
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.trailers.iter().any(|t| t.key == key)
+    }
+
+    /// Returns the value of the 1st trailer with a specific token
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.trailers
+            .iter()
+            .find(|t| t.key == key)
+            .map(|t| t.value.as_str())
+    }
+
+    /// Returns the values of all the trailers with a specific token
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.trailers
+            .iter()
+            .filter(move |t| t.key == key)
+            .map(|t| t.value.as_str())
+    }
+
+    /// Returns an iterator over the trailers
+    pub fn iter(&self) -> std::slice::Iter<'_, Trailer> {
+        self.trailers.iter()
+    }
+
+    /// Returns the number of trailers
+    pub fn len(&self) -> usize {
+        self.trailers.len()
+    }
+
+    /// Checks if the footer has no trailers
+    pub fn is_empty(&self) -> bool {
+        self.trailers.is_empty()
+    }
+}
+
+impl Display for Footer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut it = self.trailers.iter().peekable();
+        while let Some(trailer) = it.next() {
+            if it.peek().is_none() {
+                // NB: last entry
+                write!(f, "{trailer}")?;
+            } else {
+                writeln!(f, "{trailer}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> FromIterator<(K, V)> for Footer {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let trailers = iter
+            .into_iter()
+            .map(|(k, v)| Trailer::new(k.as_ref(), v.as_ref()))
+            .collect();
+        Self { trailers }
+    }
+}
+
+impl<'a> IntoIterator for &'a Footer {
+    type Item = &'a Trailer;
+    type IntoIter = std::slice::Iter<'a, Trailer>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.trailers.iter()
+    }
+}
@@ -0,0 +1,117 @@
+//! Commit kinds
+
+use std::fmt::Display;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    /// Regex for the git revert subject (eg. `Revert "feat: add a feature"`)
+    static ref REGEX_REVERT_SUBJECT: Regex =
+        Regex::new(r#"^Revert "(?P<subject>.*)"$"#).expect("Invalid regex");
+
+    /// Regex for the git revert body (eg. `This reverts commit 1a2b3c.`)
+    static ref REGEX_REVERT_BODY: Regex =
+        Regex::new(r"This reverts commit (?P<id>[0-9a-f]{7,40})").expect("Invalid regex");
+}
+
+/// Prefixes of the merge commit subjects generated by git and forges
+const MERGE_PREFIXES: [&str; 5] = [
+    "Merge branch ",
+    "Merge remote-tracking branch ",
+    "Merge pull request ",
+    "Merge tag ",
+    "Merge commit ",
+];
+
+/// Kind of commit
+///
+/// Some commits are generated by git (merge, revert, fixup, squash),
+/// and do not follow the conventional commit format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitKind {
+    /// Regular commit
+    Regular,
+    /// Merge commit (eg. `Merge branch 'feat'`)
+    Merge,
+    /// Revert commit (eg. `Revert "feat: add a feature"`, or `revert: feat: add a feature`)
+    Revert,
+    /// Fixup commit (eg. `fixup! feat: add a feature`, or `amend! feat: add a feature`)
+    Fixup,
+    /// Squash commit (eg. `squash! feat: add a feature`)
+    Squash,
+}
+
+impl Display for CommitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommitKind::Regular => write!(f, "regular"),
+            CommitKind::Merge => write!(f, "merge"),
+            CommitKind::Revert => write!(f, "revert"),
+            CommitKind::Fixup => write!(f, "fixup"),
+            CommitKind::Squash => write!(f, "squash"),
+        }
+    }
+}
+
+impl CommitKind {
+    /// Classifies a commit from its message
+    pub fn classify(message: &str) -> Self {
+        let subject = message.lines().next().unwrap_or_default().trim();
+        if MERGE_PREFIXES.iter().any(|p| subject.starts_with(p)) {
+            return CommitKind::Merge;
+        }
+        if subject.starts_with("fixup! ") || subject.starts_with("amend! ") {
+            return CommitKind::Fixup;
+        }
+        if subject.starts_with("squash! ") {
+            return CommitKind::Squash;
+        }
+        if REGEX_REVERT_SUBJECT.is_match(subject)
+            || subject.starts_with("revert: ")
+            || subject.starts_with("revert!: ")
+        {
+            return CommitKind::Revert;
+        }
+        CommitKind::Regular
+    }
+}
+
+/// Reference to the commit reverted by a revert commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertTarget {
+    /// Commit ID (full or abbreviated)
+    Id(String),
+    /// Commit subject
+    Subject(String),
+}
+
+/// Returns the commit reverted by a revert commit
+///
+/// The ID of the reverted commit is looked for in the message (`This reverts commit <id>.`, or `Refs: <id>`),
+/// then the subject (`Revert "<subject>"`, or `revert: <subject>`).
+pub fn revert_target(message: &str) -> Option<RevertTarget> {
+    if let Some(caps) = REGEX_REVERT_BODY.captures(message) {
+        return Some(RevertTarget::Id(caps["id"].to_string()));
+    }
+
+    let subject = message.lines().next().unwrap_or_default().trim();
+    if let Some(caps) = REGEX_REVERT_SUBJECT.captures(subject) {
+        return Some(RevertTarget::Subject(caps["subject"].to_string()));
+    }
+    let reverted_subject = subject
+        .strip_prefix("revert: ")
+        .or_else(|| subject.strip_prefix("revert!: "))?;
+    // NB: the conventional commits spec recommends a `Refs` footer with the reverted commit IDs
+    let refs_id = message.lines().find_map(|l| {
+        let id = l.strip_prefix("Refs: ")?.trim();
+        let is_id = id.len() >= 7 && id.chars().all(|c| c.is_ascii_hexdigit());
+        is_id.then(|| id.to_string())
+    });
+    match refs_id {
+        Some(id) => Some(RevertTarget::Id(id)),
+        None => Some(RevertTarget::Subject(reverted_subject.to_string())),
+    }
+}
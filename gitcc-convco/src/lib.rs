@@ -6,14 +6,18 @@
 //!
 //! This module is based on [Conventional commits](https://www.conventionalcommits.org/en/v1.0.0/)
 
-use std::{collections::BTreeMap, fmt::Display, io::BufRead, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
-use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
-pub use crate::util::StringExt;
+pub use crate::{error::*, footer::*, kind::*, lint::*, util::StringExt};
 
+mod error;
+mod footer;
+mod kind;
+mod lint;
 mod util;
 
 lazy_static! {
@@ -45,12 +49,16 @@ pub const BREAKING_CHANGE_KEY: &str = "BREAKING CHANGE";
 pub const BREAKING_CHANGE_KEY_DASH: &str = "BREAKING-CHANGE";
 
 /// Conventional commit message
-#[derive(Debug, Default, Clone)]
+///
+/// Parsing a message only checks its structure, the style checks are performed by the [LintRules].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ConvcoMessage {
     /// Commit type
     pub r#type: String,
-    /// Commit scope
-    pub scope: Option<String>,
+    /// Commit scopes
+    ///
+    /// Multiple scopes are separated by a comma, eg. `feat(core,cli): ...`
+    pub scopes: Vec<String>,
     /// Indicates that this is a breaking change (!)
     pub is_breaking: bool,
     /// Commit description
@@ -60,29 +68,26 @@ pub struct ConvcoMessage {
     /// Footer
     ///
     /// A footer must be a list of key: value pairs following the git trailer convention
-    pub footer: Option<IndexMap<String, String>>,
+    pub footer: Option<Footer>,
 }
 
 impl ConvcoMessage {
     /// Sets a breaking change
+    ///
+    /// If the description is not empty, a `BREAKING CHANGE` footer note is added.
     pub fn add_breaking_change(&mut self, desc: &str) -> &mut Self {
         self.is_breaking = true;
-        if let Some(entries) = &mut self.footer {
-            entries.insert(BREAKING_CHANGE_KEY.to_string(), desc.to_string());
+        if !desc.is_empty() {
+            self.add_footer_note(BREAKING_CHANGE_KEY, desc);
         }
         self
     }
 
-    /// Inserts a footer note
+    /// Adds a footer note
+    ///
+    /// A footer note with the same key can be added several times.
     pub fn add_footer_note(&mut self, key: &str, value: &str) -> &mut Self {
-        if let Some(entries) = &mut self.footer {
-            entries.insert(key.to_string(), value.to_string());
-        } else {
-            let mut entries = IndexMap::new();
-            entries.insert(key.to_string(), value.to_string());
-            self.footer = Some(entries);
-        }
-
+        self.footer.get_or_insert_with(Footer::new).push(key, value);
         self
     }
 
@@ -92,28 +97,25 @@ impl ConvcoMessage {
             return true;
         }
 
-        if let Some(entries) = &self.footer {
-            return entries.contains_key(BREAKING_CHANGE_KEY);
+        if let Some(footer) = &self.footer {
+            return footer.contains_key(BREAKING_CHANGE_KEY)
+                || footer.contains_key(BREAKING_CHANGE_KEY_DASH);
         }
         false
     }
 }
 
-/// Conventional commit error
-#[derive(Debug, thiserror::Error)]
-#[error("Invalid conventional commit:{0}")]
-pub struct ConvcoError(String);
-
 impl Display for ConvcoMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
             "{}{}{}: {}",
             self.r#type,
-            self.scope
-                .as_ref()
-                .map(|s| format!("({s})"))
-                .unwrap_or_default(),
+            if self.scopes.is_empty() {
+                "".to_string()
+            } else {
+                format!("({})", self.scopes.join(","))
+            },
             if self.is_breaking { "!" } else { "" },
             self.desc
         )?;
@@ -125,17 +127,9 @@ impl Display for ConvcoMessage {
         }
 
         // Footer
-        if let Some(entries) = &self.footer {
+        if let Some(footer) = &self.footer {
             write!(f, "\n\n")?;
-            let mut it = entries.iter().peekable();
-            while let Some((k, v)) = it.next() {
-                if it.peek().is_none() {
-                    // NB: last entry
-                    write!(f, "{k}: {v}")?;
-                } else {
-                    writeln!(f, "{k}: {v}")?;
-                }
-            }
+            write!(f, "{footer}")?;
         }
 
         Ok(())
@@ -148,168 +142,270 @@ lazy_static! {
     ///
     /// The following groups are defined
     /// - type: eg. feat
-    /// - scope: eg. (abcd)
+    /// - scope: eg. (abcd), or (abc,def)
     /// - breaking: eg. ! or ""
     /// - subject: eg. long text
     static ref REGEX_SUBJECT: Regex = Regex::new(
-        r"(?P<type>[[:word:]]+)(?P<scope>[\(][[:word:]]+[\)])?(?P<breaking>[!])?: (?P<desc>.*)"
+        r"^(?P<type>[[:word:]]+)(?P<scope>[\(][^\(\)]+[\)])?(?P<breaking>[!])?: (?P<desc>.*)$"
+    )
+    .expect("Invalid regex");
+
+    /// Lenient regex for the subject line
+    ///
+    /// Blanks around the type, scope, and separator are tolerated, as well as a missing space after the ':'.
+    static ref REGEX_SUBJECT_LENIENT: Regex = Regex::new(
+        r"^\s*(?P<type>[[:word:]]+)\s*(?P<scope>[\(][^\(\)]*[\)])?\s*(?P<breaking>[!])?\s*:\s*(?P<desc>.*\S)\s*$"
     )
     .expect("Invalid regex");
 }
 
 lazy_static! {
     static ref REGEX_FOOTER_KV: Regex =
-        Regex::new(r"(?P<key>.*): (?P<value>.*)").expect("Invalid regex");
+        Regex::new(r"^(?P<key>[^:#]+?)(?:(?P<colon>: )|(?P<hash> #))(?P<value>.*)$")
+            .expect("Invalid regex");
+}
+
+/// Default characters allowed in a scope, in addition to alphanumeric characters
+pub const DEFAULT_SCOPE_CHARS: &str = "_-/.";
+
+/// Scopes separator
+pub const SCOPES_SEPARATOR: char = ',';
+
+/// Parse options
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Characters allowed in a scope, in addition to alphanumeric characters
+    pub scope_chars: String,
+    /// Known types (empty = any type)
+    ///
+    /// An unknown type is only reported as a warning by the lenient parser.
+    pub types: Vec<String>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            scope_chars: DEFAULT_SCOPE_CHARS.to_string(),
+            types: vec![],
+        }
+    }
 }
 
 impl FromStr for ConvcoMessage {
     type Err = ConvcoError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        #[derive(Debug, PartialEq)]
-        enum Section {
-            Subject,
-            Body,
-            Footer,
+        Self::parse_with(s, &ParseOptions::default())
+    }
+}
+
+impl ConvcoMessage {
+    /// Parses a message with specific options
+    pub fn parse_with(s: &str, opts: &ParseOptions) -> Result<Self, ConvcoError> {
+        Self::parse_inner(s, opts, None)
+    }
+
+    /// Parses a message leniently
+    ///
+    /// Slightly malformed messages are recovered on a best-effort basis, and the issues
+    /// are returned as warnings:
+    /// - blanks around the separator, or a missing space after the ':'
+    /// - an invalid scope (the scopes are kept as is)
+    /// - a type which is not lowercase (the type is lowercased)
+    /// - a description starting with an uppercase letter
+    /// - an unknown type (see [ParseOptions::types])
+    /// - a missing blank line after the subject (the following lines are the body)
+    ///
+    /// An error is returned if the type and description cannot be recovered.
+    pub fn parse_lenient(
+        s: &str,
+        opts: &ParseOptions,
+    ) -> Result<(Self, Vec<ConvcoError>), ConvcoError> {
+        let mut warnings = vec![];
+        let msg = Self::parse_inner(s, opts, Some(&mut warnings))?;
+        Ok((msg, warnings))
+    }
+
+    /// Parses a message
+    ///
+    /// If `warnings` is set, the parsing is lenient, and the recovered errors are added to the warnings.
+    fn parse_inner(
+        s: &str,
+        opts: &ParseOptions,
+        mut warnings: Option<&mut Vec<ConvcoError>>,
+    ) -> Result<Self, ConvcoError> {
+        if s.trim().is_empty() {
+            return Err(ConvcoError::new(
+                ConvcoErrorKind::EmptyMessage,
+                Span::new(1, 1, 1),
+                "",
+            ));
         }
 
-        let mut r#type = String::new();
-        let mut scope: Option<String> = None;
-        let mut is_breaking: bool = false;
-        let mut desc = String::new();
-        let mut body: Option<String> = None;
-        let mut footer: Option<IndexMap<String, String>> = None;
-
-        // We parse starting from the bottom
-        let mut this_section = Section::Subject;
-        let mut is_prev_line_empty = false;
-        for (i, line_res) in s.as_bytes().lines().enumerate() {
-            let line = line_res.map_err(|e| ConvcoError(e.to_string()))?;
-
-            match (i, &this_section) {
-                (0, Section::Subject) => {
-                    // => parse 1st line
-                    let caps = match REGEX_SUBJECT.captures(s) {
-                        Some(caps) => caps,
-                        None => {
-                            return Err(ConvcoError("invalid subject line".to_string()));
-                        }
-                    };
-                    if let Some(ok) = caps.name("type") {
-                        r#type = ok.as_str().to_string();
-                        if r#type.is_empty() || !r#type.is_lowercase() {
-                            return Err(ConvcoError(
-                                "type must non empty and lowercase".to_string(),
-                            ));
-                        }
-                    } else {
-                        return Err(ConvcoError("missing subject type".to_string()));
-                    };
-                    if let Some(ok) = caps.name("scope") {
-                        let scope_raw = ok.as_str();
-                        let scope_raw = scope_raw.strip_prefix('(').unwrap();
-                        let scope_raw = scope_raw.strip_suffix(')').unwrap();
-                        if scope_raw.is_empty() || !scope_raw.is_lowercase() {
-                            return Err(ConvcoError(
-                                "scope must non empty and lowercase".to_string(),
-                            ));
-                        }
-                        scope = Some(scope_raw.to_string());
-                    };
-                    if caps.name("breaking").is_some() {
-                        is_breaking = true;
-                    };
-                    match caps.name("desc") {
-                        Some(ok) => {
-                            desc = ok.as_str().to_string();
-                            if !desc.starts_with_lowercase() {
-                                return Err(ConvcoError(
-                                    "subject must start with lowercase".to_string(),
-                                ));
-                            }
-                        }
-                        None => {
-                            return Err(ConvcoError("missing subject description".to_string()));
-                        }
-                    };
+        // NB: trailing empty lines are ignored
+        let mut lines = s.lines().collect::<Vec<_>>();
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+
+        // => parse 1st line
+        let line = lines[0];
+        let is_valid_subject = REGEX_SUBJECT
+            .captures(line)
+            .and_then(|caps| caps.name("desc"))
+            .is_some_and(|desc| !desc.as_str().trim().is_empty());
+        let caps = if is_valid_subject {
+            REGEX_SUBJECT.captures(line).unwrap()
+        } else {
+            let err = diagnose_subject(line);
+            match REGEX_SUBJECT_LENIENT.captures(line) {
+                Some(caps) if warnings.is_some() => {
+                    recover(&mut warnings, err)?;
+                    caps
                 }
-                (1, Section::Subject) => {
-                    // >> line after subject
-                    if line.is_empty() {
-                        is_prev_line_empty = true;
-                        this_section = Section::Body;
-                    } else {
-                        return Err(ConvcoError(
-                            "body must be separated by an empty line".to_string(),
-                        ));
+                _ => return Err(err),
+            }
+        };
+        // NB: columns start at 1
+        let column_of = |m: &regex::Match| line[..m.start()].chars().count() + 1;
+
+        let type_match = caps.name("type").unwrap();
+        let mut r#type = type_match.as_str().to_string();
+        let scopes = match caps.name("scope") {
+            Some(ok) => {
+                // NB: the scopes start after the '('
+                let raw = &ok.as_str()[1..ok.len() - 1];
+                match parse_scopes(raw, column_of(&ok) + 1, opts) {
+                    Ok(scopes) => scopes,
+                    Err(err) => {
+                        recover(&mut warnings, err)?;
+                        raw.split(SCOPES_SEPARATOR)
+                            .map(|s| s.trim())
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_string())
+                            .collect()
                     }
                 }
-                (_, Section::Subject) => {
-                    unreachable!()
-                }
-                (_, Section::Body) => {
-                    // eprintln!("{:#?}", line);
-
-                    // NOTES:
-                    // OK here it gets a bit tricky to split the body and the footer.
-                    // The footer is the last paragraph and must be a list of key-value pairs (K: V),
-                    // where K is a word token (separation is made with '-', with the execption of the value 'BREAKING CHANGE').
-                    //
-                    // So, we use the heuristics that, for each line, if the previous line is blank,
-                    // and if that line starts with a valid key/value pair, we are now part of the footer.
-                    if is_prev_line_empty {
-                        if let Some(caps) = REGEX_FOOTER_KV.captures(&line) {
-                            let key = caps.name("key").unwrap().as_str();
-                            if is_valid_footer_token(key) {
-                                // => we are part of the footer
-                                let value = caps.name("value").unwrap().as_str();
-                                let mut m = IndexMap::new();
-                                m.insert(key.to_string(), value.to_string());
-                                footer = Some(m);
-                                is_prev_line_empty = false;
-                                this_section = Section::Footer;
-                                // NB: removing the '\n' at the end of the body
-                                body = body.map(|b| b.trim().to_string());
-                                continue;
-                            }
-                        };
-                    }
+            }
+            None => vec![],
+        };
+        let is_breaking = caps.name("breaking").is_some();
+        let desc_match = caps.name("desc").unwrap();
+        let desc = desc_match.as_str().to_string();
+
+        if let Some(w) = warnings.as_deref_mut() {
+            let type_span = Span::new(1, column_of(&type_match), r#type.chars().count());
+            if r#type != r#type.to_lowercase() {
+                let lowercase = r#type.to_lowercase();
+                w.push(
+                    ConvcoError::new(ConvcoErrorKind::TypeCase, type_span, &r#type)
+                        .with_suggestion(format!("use '{lowercase}'")),
+                );
+                r#type = lowercase;
+            }
+            if !opts.types.is_empty() && !opts.types.contains(&r#type) {
+                w.push(
+                    ConvcoError::new(ConvcoErrorKind::UnknownType, type_span, &r#type)
+                        .with_suggestion(format!("valid types are: {}", opts.types.join(", "))),
+                );
+            }
+            if desc.starts_with(char::is_uppercase) {
+                let first = desc.chars().next().unwrap_or_default();
+                w.push(
+                    ConvcoError::new(
+                        ConvcoErrorKind::DescriptionCase,
+                        Span::new(1, column_of(&desc_match), 1),
+                        &first.to_string(),
+                    )
+                    .with_suggestion("start the description with a lowercase letter"),
+                );
+            }
+        }
 
-                    let mut b = if let Some(mut b) = body {
-                        b.push('\n');
-                        b
-                    } else {
-                        "".to_string()
-                    };
-                    b.push_str(&line);
-                    body = Some(b);
-                    is_prev_line_empty = line.is_empty();
-                }
-                (_, Section::Footer) => {
-                    if let Some(caps) = REGEX_FOOTER_KV.captures(&line) {
-                        let key = caps.name("key").unwrap().as_str();
-                        if is_valid_footer_token(key) {
-                            // => we are part of the footer
-                            let value = caps.name("value").unwrap().as_str();
-                            if let Some(f) = &mut footer {
-                                f.insert(key.to_string(), value.to_string());
-                            } else {
-                                unreachable!()
-                            }
-                        } else {
-                            return Err(ConvcoError(format!("invalid footer key: '{key}'")));
+        // >> line after subject
+        let mut rest_start = 2;
+        if let Some(line) = lines.get(1) {
+            if !line.is_empty() {
+                recover(
+                    &mut warnings,
+                    ConvcoError::new(
+                        ConvcoErrorKind::MissingBlankLine,
+                        Span::new(2, 1, line.chars().count()),
+                        line,
+                    )
+                    .with_suggestion("insert an empty line after the subject"),
+                )?;
+                // NB: lenient mode, the body starts right after the subject
+                rest_start = 1;
+            }
+        }
+
+        // NOTES:
+        // The footer is the last paragraph, and must start with a key-value pair (K: V),
+        // where K is a word token (separation is made with '-', with the execption of the value 'BREAKING CHANGE').
+        // Following the git trailers convention, a token can be repeated, and the lines which start
+        // with a white space are continuation lines of the previous value.
+        let rest = if lines.len() > rest_start {
+            &lines[rest_start..]
+        } else {
+            &[]
+        };
+        let footer_start = rest
+            .iter()
+            .rposition(|l| l.is_empty())
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let footer_lines = &rest[footer_start..];
+        // NB: a paragraph starting with a `#` trailer (eg. `Closes #123`) may be prose,
+        // so it is only a footer if all its lines are trailers or continuation lines
+        let has_footer = match footer_lines.first().and_then(|l| parse_trailer(l)) {
+            Some(trailer) if trailer.separator == Separator::Hash => footer_lines
+                .iter()
+                .all(|l| l.starts_with(char::is_whitespace) || parse_trailer(l).is_some()),
+            Some(_) => true,
+            None => false,
+        };
+        let body_lines = if has_footer {
+            &rest[..footer_start]
+        } else {
+            rest
+        };
+
+        let body = body_lines.join("\n").trim_end().to_string();
+        let body = if body.is_empty() { None } else { Some(body) };
+
+        let mut footer = None;
+        if has_footer {
+            let mut f = Footer::new();
+            for (i, line) in footer_lines.iter().enumerate() {
+                let is_continuation = line.starts_with(char::is_whitespace);
+                match (parse_trailer(line), f.trailers.last_mut()) {
+                    (Some(trailer), _) if !is_continuation => {
+                        f.trailers.push(trailer);
+                    }
+                    (_, Some(last)) => {
+                        if !is_continuation {
+                            let line_no = rest_start + footer_start + i + 1;
+                            recover(&mut warnings, invalid_footer_line(line, line_no))?;
                         }
-                    } else {
-                        return Err(ConvcoError("invalid footer line".to_string()));
-                    };
+                        // NB: lenient mode, an invalid line is kept in the previous value
+                        last.value.push('\n');
+                        last.value.push_str(line);
+                    }
+                    (_, None) => {
+                        // NB: lenient mode, a line without a previous trailer is dropped
+                        let line_no = rest_start + footer_start + i + 1;
+                        recover(&mut warnings, invalid_footer_line(line, line_no))?;
+                    }
                 }
             }
+            footer = Some(f);
         }
 
         // ⮑
         Ok(Self {
             r#type,
-            scope,
+            scopes,
             is_breaking,
             desc,
             body,
@@ -318,11 +414,222 @@ impl FromStr for ConvcoMessage {
     }
 }
 
+/// Recovers from an error in lenient mode
+///
+/// In lenient mode (`warnings` is set), the error is added to the warnings, otherwise it is returned.
+fn recover(
+    warnings: &mut Option<&mut Vec<ConvcoError>>,
+    err: ConvcoError,
+) -> Result<(), ConvcoError> {
+    match warnings.as_deref_mut() {
+        Some(w) => {
+            w.push(err);
+            Ok(())
+        }
+        None => Err(err),
+    }
+}
+
+/// Finds the error in an invalid subject line
+///
+/// The subject is scanned character by character to locate the offending token,
+/// and to suggest a fix.
+fn diagnose_subject(line: &str) -> ConvcoError {
+    let chars = line.chars().collect::<Vec<_>>();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    // NB: columns start at 1
+    let error_at = |kind: ConvcoErrorKind, i: usize| {
+        let token = chars.get(i).map(|c| c.to_string()).unwrap_or_default();
+        ConvcoError::new(kind, Span::new(1, i + 1, 1), &token)
+    };
+
+    // type
+    let mut i = chars.iter().take_while(|c| is_word(c)).count();
+    if i == 0 {
+        return error_at(ConvcoErrorKind::MissingType, 0)
+            .with_suggestion("the subject must start with a type, eg. 'feat: <description>'");
+    }
+    let r#type = chars[..i].iter().collect::<String>();
+
+    // scope
+    let mut scope = String::new();
+    if chars.get(i) == Some(&'(') {
+        let start = i;
+        let len = chars[start + 1..]
+            .iter()
+            .take_while(|c| **c != ')' && **c != '(')
+            .count();
+        i = start + 1 + len;
+        match chars.get(i) {
+            Some(')') if len > 0 => {
+                scope = chars[start..=i].iter().collect();
+                i += 1;
+            }
+            Some(')') => {
+                return error_at(ConvcoErrorKind::InvalidScope, i)
+                    .with_suggestion("the scope cannot be empty");
+            }
+            _ => {
+                // NB: the scope is not closed, we point to the likely end of the scope
+                let end = chars[start + 1..]
+                    .iter()
+                    .position(|c| *c == ':' || *c == ' ' || *c == '(')
+                    .map(|p| start + 1 + p)
+                    .unwrap_or(chars.len());
+                return ConvcoError::new(
+                    ConvcoErrorKind::InvalidScope,
+                    Span::new(1, end + 1, 1),
+                    &chars.get(end).map(|c| c.to_string()).unwrap_or_default(),
+                )
+                .with_suggestion("the scope must be closed with ')'");
+            }
+        }
+    }
+
+    // breaking
+    let mut breaking = "";
+    if chars.get(i) == Some(&'!') {
+        breaking = "!";
+        i += 1;
+    }
+
+    // separator
+    let header = format!("{type}{scope}{breaking}");
+    let rest = chars[i..].iter().collect::<String>();
+    let rest = rest.trim_start_matches([':', ' ']);
+    let suggestion = format!("{header}: {rest}");
+    match (chars.get(i), chars.get(i + 1)) {
+        (Some(':'), Some(' ')) => {}
+        (Some(':'), _) => {
+            return error_at(ConvcoErrorKind::MissingSeparator, i + 1).with_suggestion(suggestion);
+        }
+        (Some(c), _) if !c.is_whitespace() && scope.is_empty() && breaking.is_empty() => {
+            // NB: a character which is not a separator, but which is followed by a separator
+            let has_sep_after = chars[i..]
+                .iter()
+                .take_while(|c| !c.is_whitespace())
+                .any(|c| *c == ':');
+            if has_sep_after {
+                return error_at(ConvcoErrorKind::InvalidType, i)
+                    .with_suggestion("the type must only contain alphanumeric characters");
+            }
+            return error_at(ConvcoErrorKind::MissingSeparator, i).with_suggestion(suggestion);
+        }
+        _ => {
+            return error_at(ConvcoErrorKind::MissingSeparator, i).with_suggestion(suggestion);
+        }
+    }
+
+    // description
+    if chars[i + 2..].iter().all(|c| c.is_whitespace()) {
+        return error_at(ConvcoErrorKind::MissingDescription, i + 2)
+            .with_suggestion("add a description after the separator");
+    }
+
+    ConvcoError::new(
+        ConvcoErrorKind::InvalidSubject,
+        Span::new(1, 1, chars.len()),
+        line,
+    )
+    .with_suggestion("the subject must follow the format '<type>[(<scope>)][!]: <description>'")
+}
+
+/// Parses the scopes (eg. `core,cli`)
+///
+/// The `column` is the column of the 1st scope character in the subject line.
+fn parse_scopes(raw: &str, column: usize, opts: &ParseOptions) -> Result<Vec<String>, ConvcoError> {
+    let mut scopes = vec![];
+    let mut column = column;
+    for scope_raw in raw.split(SCOPES_SEPARATOR) {
+        let scope = scope_raw.trim();
+        // NB: column of the 1st non-blank character
+        let scope_column =
+            column + (scope_raw.chars().count() - scope_raw.trim_start().chars().count());
+        if scope.is_empty() {
+            return Err(ConvcoError::new(
+                ConvcoErrorKind::InvalidScope,
+                Span::new(1, scope_column, 1),
+                "",
+            )
+            .with_suggestion("the scope cannot be empty"));
+        }
+        if let Some((j, c)) = scope
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_alphanumeric() && !opts.scope_chars.contains(*c))
+        {
+            return Err(ConvcoError::new(
+                ConvcoErrorKind::InvalidScope,
+                Span::new(1, scope_column + j, 1),
+                &c.to_string(),
+            )
+            .with_suggestion(format!(
+                "the scope must only contain alphanumeric characters or '{}'",
+                opts.scope_chars
+            )));
+        }
+        scopes.push(scope.to_string());
+        column += scope_raw.chars().count() + 1;
+    }
+    Ok(scopes)
+}
+
+/// Parses a trailer line (eg. `Refs: #123`)
+///
+/// None is returned if the line does not start with a valid footer token.
+fn parse_trailer(line: &str) -> Option<Trailer> {
+    let caps = REGEX_FOOTER_KV.captures(line)?;
+    let key = caps.name("key").unwrap().as_str();
+    if !is_valid_footer_token(key) {
+        return None;
+    }
+    let separator = if caps.name("hash").is_some() {
+        Separator::Hash
+    } else {
+        Separator::Colon
+    };
+    let value = caps.name("value").unwrap().as_str();
+    // NB: the `#` separator is only used for issue references (eg. `Closes #123`)
+    if separator == Separator::Hash && !is_issue_ref(value) {
+        return None;
+    }
+    Some(Trailer::with_separator(key, separator, value))
+}
+
+/// Returns the error of an invalid footer line (line numbers start at 1)
+fn invalid_footer_line(line: &str, line_no: usize) -> ConvcoError {
+    if let Some(caps) = REGEX_FOOTER_KV.captures(line) {
+        let key = caps.name("key").unwrap().as_str();
+        if !is_valid_footer_token(key) {
+            return ConvcoError::new(
+                ConvcoErrorKind::InvalidFooterToken,
+                Span::new(line_no, 1, key.chars().count()),
+                key,
+            )
+            .with_suggestion(format!(
+                "footer tokens cannot contain white spaces, eg. '{}'",
+                key.split_whitespace().collect::<Vec<_>>().join("-")
+            ));
+        }
+    }
+    ConvcoError::new(
+        ConvcoErrorKind::InvalidFooterLine,
+        Span::new(line_no, 1, line.chars().count().max(1)),
+        line,
+    )
+    .with_suggestion("footer lines must be '<token>: <value>' pairs, or continuation lines starting with a white space")
+}
+
+/// Checks if a `#` trailer value is an issue reference (eg. `123`)
+fn is_issue_ref(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
 /// Checks if a string is valid footer token
 ///
 /// A valid footer token is a word token with no white space, with the exception of the value 'BREAKING CHANGE'
 fn is_valid_footer_token(value: &str) -> bool {
-    if value.contains(' ') {
+    if value.contains(char::is_whitespace) {
         return value == BREAKING_CHANGE_KEY;
     }
     true
@@ -341,6 +648,15 @@ mod tests {
         assert_eq!(caps.name("value").unwrap().as_str(), "#123");
     }
 
+    #[test]
+    fn regex_footer_hash() {
+        let s = "Closes #123";
+        let caps = REGEX_FOOTER_KV.captures(s).unwrap();
+        assert_eq!(caps.name("key").unwrap().as_str(), "Closes");
+        assert!(caps.name("hash").is_some());
+        assert_eq!(caps.name("value").unwrap().as_str(), "123");
+    }
+
     #[test]
     fn regex_footer_breaking_change() {
         let s = "BREAKING CHANGE: This is a breaking change";
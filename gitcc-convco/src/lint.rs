@@ -0,0 +1,279 @@
+//! Lint rules
+//!
+//! Each rule is named, and can be individually configured with a severity level (off, warn, error).
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ConvcoMessage;
+
+/// Severity level of a lint rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule is disabled
+    Off,
+    /// A rule violation is reported as a warning
+    Warn,
+    /// A rule violation is reported as an error
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Off => write!(f, "off"),
+            Severity::Warn => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A lint rule with a value (eg. a max length)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule<T> {
+    /// Severity level
+    pub level: Severity,
+    /// Rule value
+    pub value: T,
+}
+
+impl<T> Rule<T> {
+    /// Creates a new rule
+    pub fn new(level: Severity, value: T) -> Self {
+        Self { level, value }
+    }
+}
+
+/// Lint rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintRules {
+    /// The type must be lowercase
+    pub type_case: Severity,
+    /// The type must be one of the listed types (no check if empty)
+    pub type_enum: Rule<Vec<String>>,
+    /// The scopes must be lowercase
+    pub scope_case: Severity,
+    /// The scopes must be in the listed scopes (no check if empty)
+    pub scope_enum: Rule<Vec<String>>,
+    /// The scope is required
+    pub scope_required: Severity,
+    /// The description must start with a lowercase character
+    pub desc_case: Severity,
+    /// The description must not end with a period
+    pub desc_full_stop: Severity,
+    /// Max length of the header (1st line)
+    pub header_max_length: Rule<usize>,
+    /// Max length of the body lines
+    pub body_max_line_length: Rule<usize>,
+    /// The footer is required for the listed types
+    pub footer_required: Rule<Vec<String>>,
+    /// Forbidden words or phrases in the header and body (case insensitive)
+    ///
+    /// An entry matches whole words only (eg. `wip` does not match `wipe`), and can span several words (eg. `work in progress`).
+    pub forbidden_words: Rule<Vec<String>>,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        Self {
+            type_case: Severity::Error,
+            type_enum: Rule::new(Severity::Error, vec![]),
+            scope_case: Severity::Error,
+            scope_enum: Rule::new(Severity::Off, vec![]),
+            scope_required: Severity::Off,
+            desc_case: Severity::Error,
+            desc_full_stop: Severity::Off,
+            header_max_length: Rule::new(Severity::Off, 72),
+            body_max_line_length: Rule::new(Severity::Off, 100),
+            footer_required: Rule::new(Severity::Off, vec![]),
+            forbidden_words: Rule::new(Severity::Off, vec![]),
+        }
+    }
+}
+
+/// A lint rule violation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// Rule name (eg. `header_max_length`)
+    pub rule: &'static str,
+    /// Severity
+    pub severity: Severity,
+    /// Message
+    pub message: String,
+}
+
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}]", self.message, self.rule)
+    }
+}
+
+impl LintRules {
+    /// Checks a conventional message against the rules
+    ///
+    /// Rules which are turned off are not checked.
+    pub fn check(&self, msg: &ConvcoMessage) -> Vec<LintIssue> {
+        let mut issues = vec![];
+        let mut report = |rule: &'static str, severity: Severity, message: String| {
+            if severity != Severity::Off {
+                issues.push(LintIssue {
+                    rule,
+                    severity,
+                    message,
+                });
+            }
+        };
+
+        // type
+        if has_uppercase(&msg.r#type) {
+            report(
+                "type_case",
+                self.type_case,
+                format!("type '{}' must be lowercase", msg.r#type),
+            );
+        }
+        if !self.type_enum.value.is_empty() && !self.type_enum.value.contains(&msg.r#type) {
+            report(
+                "type_enum",
+                self.type_enum.level,
+                format!(
+                    "type '{}' is not allowed (valid types: {})",
+                    msg.r#type,
+                    self.type_enum.value.join(", ")
+                ),
+            );
+        }
+
+        // scope
+        for scope in &msg.scopes {
+            if has_uppercase(scope) {
+                report(
+                    "scope_case",
+                    self.scope_case,
+                    format!("scope '{scope}' must be lowercase"),
+                );
+            }
+            if !self.scope_enum.value.is_empty() && !self.scope_enum.value.contains(scope) {
+                report(
+                    "scope_enum",
+                    self.scope_enum.level,
+                    format!(
+                        "scope '{scope}' is not allowed (valid scopes: {})",
+                        self.scope_enum.value.join(", ")
+                    ),
+                );
+            }
+        }
+        if msg.scopes.is_empty() {
+            report(
+                "scope_required",
+                self.scope_required,
+                "scope is required".to_string(),
+            );
+        }
+
+        // description
+        if msg.desc.chars().next().is_some_and(|c| c.is_uppercase()) {
+            report(
+                "desc_case",
+                self.desc_case,
+                "description must start with a lowercase character".to_string(),
+            );
+        }
+        if msg.desc.ends_with('.') {
+            report(
+                "desc_full_stop",
+                self.desc_full_stop,
+                "description must not end with a period".to_string(),
+            );
+        }
+
+        // header
+        let header = msg
+            .to_string()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let header_len = header.chars().count();
+        if header_len > self.header_max_length.value {
+            report(
+                "header_max_length",
+                self.header_max_length.level,
+                format!(
+                    "header is too long ({header_len} > {} characters)",
+                    self.header_max_length.value
+                ),
+            );
+        }
+
+        // body
+        if let Some(body) = &msg.body {
+            for (i, line) in body.lines().enumerate() {
+                let line_len = line.chars().count();
+                if line_len > self.body_max_line_length.value {
+                    report(
+                        "body_max_line_length",
+                        self.body_max_line_length.level,
+                        format!(
+                            "body line {} is too long ({line_len} > {} characters)",
+                            i + 1,
+                            self.body_max_line_length.value
+                        ),
+                    );
+                }
+            }
+        }
+
+        // footer
+        if msg.footer.is_none() && self.footer_required.value.contains(&msg.r#type) {
+            report(
+                "footer_required",
+                self.footer_required.level,
+                format!("footer is required for type '{}'", msg.r#type),
+            );
+        }
+
+        // forbidden words
+        let text = format!("{}\n{}", header, msg.body.as_deref().unwrap_or_default());
+        for word in &self.forbidden_words.value {
+            if contains_words(&text, word) {
+                report(
+                    "forbidden_words",
+                    self.forbidden_words.level,
+                    format!("forbidden word '{word}'"),
+                );
+            }
+        }
+
+        issues
+    }
+}
+
+/// Checks if a text contains a word or phrase (case insensitive)
+///
+/// The match must not be preceded or followed by an alphanumeric character.
+fn contains_words(text: &str, words: &str) -> bool {
+    if words.is_empty() {
+        return false;
+    }
+    // NB: the ASCII lowercase keeps the byte offsets
+    let text = text.to_ascii_lowercase();
+    let words = words.to_ascii_lowercase();
+    let is_word_char = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    text.match_indices(&words).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + words.len()..].chars().next();
+        let is_split_start = is_word_char(before) && is_word_char(words.chars().next());
+        let is_split_end = is_word_char(after) && is_word_char(words.chars().next_back());
+        !is_split_start && !is_split_end
+    })
+}
+
+/// Checks if a string contains an uppercase character
+fn has_uppercase(s: &str) -> bool {
+    s.chars().any(|c| c.is_uppercase())
+}
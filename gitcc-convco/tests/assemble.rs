@@ -1,21 +1,20 @@
 //! Assemble
 
-use gitcc_convco::{ConvcoMessage, BREAKING_CHANGE_KEY};
-use indexmap::IndexMap;
+use gitcc_convco::{ConvcoMessage, Footer, BREAKING_CHANGE_KEY};
 
 #[test]
 fn assemble_ex_1() {
     let ex_1 = include_str!("files/ex_1.txt");
     let ex_1 = ex_1.strip_suffix('\n').unwrap_or(ex_1);
 
-    let mut footer = IndexMap::new();
-    footer.insert(
-        BREAKING_CHANGE_KEY.to_string(),
-        "`extends` key in config file is now used for extending other config files".to_string(),
+    let mut footer = Footer::new();
+    footer.push(
+        BREAKING_CHANGE_KEY,
+        "`extends` key in config file is now used for extending other config files",
     );
     let msg = ConvcoMessage {
         r#type: "feat".to_string(),
-        scope: None,
+        scopes: vec![],
         is_breaking: false,
         desc: "allow provided config object to extend other configs".to_string(),
         body: None,
@@ -33,7 +32,7 @@ fn assemble_ex_2() {
 
     let msg = ConvcoMessage {
         r#type: "feat".to_string(),
-        scope: None,
+        scopes: vec![],
         is_breaking: true,
         desc: "send an email to the customer when a product is shipped".to_string(),
         body: None,
@@ -57,12 +56,12 @@ Remove timeouts which were used to mitigate the racing issue but are
 obsolete now.
 "
     .trim();
-    let mut footer = IndexMap::new();
-    footer.insert("Reviewed-by".to_string(), "Z".to_string());
-    footer.insert("Refs".to_string(), "#123".to_string());
+    let mut footer = Footer::new();
+    footer.push("Reviewed-by", "Z");
+    footer.push("Refs", "#123");
     let msg = ConvcoMessage {
         r#type: "fix".to_string(),
-        scope: None,
+        scopes: vec![],
         is_breaking: false,
         desc: "prevent racing of requests".to_string(),
         body: Some(body.to_string()),
@@ -72,3 +71,29 @@ obsolete now.
 
     assert_eq!(msg, ex_6);
 }
+
+#[test]
+fn assemble_roundtrip_footer() {
+    let raw = "feat: add a feature
+
+Some body
+
+Co-authored-by: A <a@example.com>
+Co-authored-by: B <b@example.com>
+BREAKING CHANGE: the config file format changed,
+  and the old format is not supported anymore
+Refs: #123";
+    let msg = raw.parse::<ConvcoMessage>().unwrap();
+    assert_eq!(msg.to_string(), raw);
+}
+
+#[test]
+fn assemble_roundtrip_footer_hash() {
+    let raw = "fix: fix a bug
+
+Closes #123
+Refs #45
+Reviewed-by: Z";
+    let msg = raw.parse::<ConvcoMessage>().unwrap();
+    assert_eq!(msg.to_string(), raw);
+}
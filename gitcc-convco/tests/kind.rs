@@ -0,0 +1,48 @@
+//! Tests for commit kinds
+
+use gitcc_convco::{revert_target, CommitKind, RevertTarget};
+
+#[test]
+fn kind_classify() {
+    let cases = [
+        ("feat: add a feature", CommitKind::Regular),
+        ("Merge branch 'feat' into main", CommitKind::Merge),
+        ("Merge pull request #12 from user/feat", CommitKind::Merge),
+        ("Revert \"feat: add a feature\"", CommitKind::Revert),
+        ("revert: feat: add a feature", CommitKind::Revert),
+        ("fixup! feat: add a feature", CommitKind::Fixup),
+        ("amend! feat: add a feature", CommitKind::Fixup),
+        ("squash! feat: add a feature", CommitKind::Squash),
+        ("Merge the 2 configs", CommitKind::Regular),
+    ];
+    for (msg, kind) in cases {
+        assert_eq!(CommitKind::classify(msg), kind, "{msg}");
+    }
+}
+
+#[test]
+fn kind_revert_target() {
+    let msg = "Revert \"feat: add a feature\"
+
+This reverts commit 8a3b6f0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a.
+";
+    assert_eq!(
+        revert_target(msg),
+        Some(RevertTarget::Id(
+            "8a3b6f0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a".to_string()
+        ))
+    );
+    assert_eq!(
+        revert_target("Revert \"feat: add a feature\""),
+        Some(RevertTarget::Subject("feat: add a feature".to_string()))
+    );
+    assert_eq!(
+        revert_target("revert: feat: add a feature\n\nRefs: 8a3b6f0"),
+        Some(RevertTarget::Id("8a3b6f0".to_string()))
+    );
+    assert_eq!(
+        revert_target("revert: feat: add a feature"),
+        Some(RevertTarget::Subject("feat: add a feature".to_string()))
+    );
+    assert_eq!(revert_target("feat: add a feature"), None);
+}
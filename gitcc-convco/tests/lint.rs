@@ -0,0 +1,108 @@
+//! Tests for the lint rules
+
+use gitcc_convco::{ConvcoMessage, LintRules, Rule, Severity};
+
+fn rules_of(issues: &[gitcc_convco::LintIssue]) -> Vec<&str> {
+    issues.iter().map(|i| i.rule).collect()
+}
+
+#[test]
+fn lint_default_rules() {
+    let rules = LintRules::default();
+
+    let msg = "feat(api): send an email".parse::<ConvcoMessage>().unwrap();
+    assert!(rules.check(&msg).is_empty());
+
+    let msg = "Feat(API): Send an email".parse::<ConvcoMessage>().unwrap();
+    let issues = rules.check(&msg);
+    assert_eq!(rules_of(&issues), ["type_case", "scope_case", "desc_case"]);
+    assert!(issues.iter().all(|i| i.severity == Severity::Error));
+}
+
+#[test]
+fn lint_rules_off() {
+    let rules = LintRules {
+        type_case: Severity::Off,
+        scope_case: Severity::Off,
+        desc_case: Severity::Warn,
+        ..Default::default()
+    };
+
+    let msg = "Feat(API): Send an email".parse::<ConvcoMessage>().unwrap();
+    let issues = rules.check(&msg);
+    assert_eq!(rules_of(&issues), ["desc_case"]);
+    assert_eq!(issues[0].severity, Severity::Warn);
+}
+
+#[test]
+fn lint_rules_values() {
+    let rules = LintRules {
+        type_enum: Rule::new(Severity::Error, vec!["feat".to_string()]),
+        scope_enum: Rule::new(Severity::Error, vec!["api".to_string()]),
+        desc_full_stop: Severity::Error,
+        header_max_length: Rule::new(Severity::Error, 20),
+        body_max_line_length: Rule::new(Severity::Warn, 10),
+        footer_required: Rule::new(Severity::Error, vec!["fix".to_string()]),
+        forbidden_words: Rule::new(Severity::Error, vec!["wip".to_string()]),
+        ..Default::default()
+    };
+
+    let msg = "fix(ui): fix the WIP button.\n\nThis line is long"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    let issues = rules.check(&msg);
+    assert_eq!(
+        rules_of(&issues),
+        [
+            "type_enum",
+            "scope_enum",
+            "desc_full_stop",
+            "header_max_length",
+            "body_max_line_length",
+            "footer_required",
+            "forbidden_words"
+        ]
+    );
+}
+
+#[test]
+fn lint_forbidden_words() {
+    let rules = LintRules {
+        forbidden_words: Rule::new(
+            Severity::Error,
+            vec![
+                "wip".to_string(),
+                "work in progress".to_string(),
+                "TODO:".to_string(),
+            ],
+        ),
+        ..Default::default()
+    };
+
+    let msg = "feat: wipe the cache".parse::<ConvcoMessage>().unwrap();
+    assert!(rules.check(&msg).is_empty());
+
+    let msg = "feat: add a button\n\nThis is a Work in  progress"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert!(rules.check(&msg).is_empty());
+
+    let msg = "feat: add a button\n\nThis is a Work in progress, todo: tests"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(
+        rules_of(&rules.check(&msg)),
+        ["forbidden_words", "forbidden_words"]
+    );
+}
+
+#[test]
+fn lint_scope_required() {
+    let rules = LintRules {
+        scope_required: Severity::Error,
+        ..Default::default()
+    };
+
+    let msg = "feat: send an email".parse::<ConvcoMessage>().unwrap();
+    assert_eq!(rules_of(&rules.check(&msg)), ["scope_required"]);
+}
@@ -1,13 +1,13 @@
 //! Tests for parsing
 
-use gitcc_convco::ConvcoMessage;
+use gitcc_convco::{ConvcoErrorKind, ConvcoMessage, ParseOptions, Separator, Span, Trailer};
 
 #[test]
 fn parse_ex_1() {
     let ex = include_str!("files/ex_1.txt");
     let msg = ex.parse::<ConvcoMessage>().unwrap();
     assert_eq!(msg.r#type, "feat");
-    assert!(msg.scope.is_none());
+    assert!(msg.scopes.is_empty());
     assert!(!msg.is_breaking);
     assert_eq!(
         msg.desc,
@@ -21,7 +21,7 @@ fn parse_ex_2() {
     let ex = include_str!("files/ex_2.txt");
     let msg = ex.parse::<ConvcoMessage>().unwrap();
     assert_eq!(msg.r#type, "feat");
-    assert!(msg.scope.is_none());
+    assert!(msg.scopes.is_empty());
     assert!(msg.is_breaking);
     assert_eq!(
         msg.desc,
@@ -36,7 +36,7 @@ fn parse_ex_3() {
     let ex = include_str!("files/ex_3.txt");
     let msg = ex.parse::<ConvcoMessage>().unwrap();
     assert_eq!(msg.r#type, "feat");
-    assert_eq!(msg.scope.unwrap(), "api");
+    assert_eq!(msg.scopes, ["api"]);
     assert!(msg.is_breaking);
     assert_eq!(
         msg.desc,
@@ -51,7 +51,7 @@ fn parse_ex_4() {
     let ex = include_str!("files/ex_4.txt");
     let msg = ex.parse::<ConvcoMessage>().unwrap();
     assert_eq!(msg.r#type, "chore");
-    assert!(msg.scope.is_none());
+    assert!(msg.scopes.is_empty());
     assert!(msg.is_breaking);
     assert_eq!(msg.desc, "drop support for Node 6");
     assert!(msg.body.is_none());
@@ -63,7 +63,7 @@ fn parse_ex_5() {
     let ex = include_str!("files/ex_5.txt");
     let msg = ex.parse::<ConvcoMessage>().unwrap();
     assert_eq!(msg.r#type, "docs");
-    assert!(msg.scope.is_none());
+    assert!(msg.scopes.is_empty());
     assert!(!msg.is_breaking);
     assert_eq!(msg.desc, "correct spelling of CHANGELOG");
     assert!(msg.body.is_none());
@@ -75,7 +75,7 @@ fn parse_ex_6() {
     let ex = include_str!("files/ex_6.txt");
     let msg = ex.parse::<ConvcoMessage>().unwrap();
     assert_eq!(msg.r#type, "fix");
-    assert!(msg.scope.is_none());
+    assert!(msg.scopes.is_empty());
     assert!(!msg.is_breaking);
     assert_eq!(msg.desc, "prevent racing of requests");
     assert_eq!(
@@ -92,3 +92,304 @@ obsolete now.
     assert!(msg.footer.clone().unwrap().contains_key("Reviewed-by"));
     assert!(msg.footer.unwrap().contains_key("Refs"));
 }
+
+#[test]
+fn parse_error_missing_separator() {
+    let err = "feat add a feature".parse::<ConvcoMessage>().unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingSeparator);
+    assert_eq!(err.span, Span::new(1, 5, 1));
+    assert_eq!(err.token, " ");
+    assert_eq!(err.suggestion.as_deref(), Some("feat: add a feature"));
+
+    let err = "feat(api)!:add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingSeparator);
+    assert_eq!(err.span, Span::new(1, 12, 1));
+    assert_eq!(err.suggestion.as_deref(), Some("feat(api)!: add a feature"));
+}
+
+#[test]
+fn parse_error_subject() {
+    let err = "".parse::<ConvcoMessage>().unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::EmptyMessage);
+
+    let err = ": add a feature".parse::<ConvcoMessage>().unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingType);
+    assert_eq!(err.span, Span::new(1, 1, 1));
+
+    let err = "fe@t: add a feature".parse::<ConvcoMessage>().unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidType);
+    assert_eq!(err.span, Span::new(1, 3, 1));
+    assert_eq!(err.token, "@");
+
+    let err = "feat(): add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidScope);
+    assert_eq!(err.span, Span::new(1, 6, 1));
+
+    let err = "feat(api: add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidScope);
+    assert_eq!(err.span, Span::new(1, 9, 1));
+
+    let err = "feat:  ".parse::<ConvcoMessage>().unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingDescription);
+    assert_eq!(err.span, Span::new(1, 7, 1));
+}
+
+#[test]
+fn parse_error_body_footer() {
+    let err = "feat: add a feature\nbody"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingBlankLine);
+    assert_eq!(err.span, Span::new(2, 1, 4));
+
+    let err = "feat: add a feature\n\nRefs: #123\nReviewed by: Z"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidFooterToken);
+    assert_eq!(err.span, Span::new(4, 1, 11));
+    assert_eq!(err.token, "Reviewed by");
+
+    let err = "feat: add a feature\n\nRefs: #123\nsome text"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidFooterLine);
+    assert_eq!(err.span, Span::new(4, 1, 9));
+}
+
+#[test]
+fn parse_error_render() {
+    let msg = "feat add a feature";
+    let err = msg.parse::<ConvcoMessage>().unwrap_err();
+    assert_eq!(
+        err.render(msg),
+        "error: missing ': ' separator (line 1, column 5)
+  |
+1 | feat add a feature
+  |     ^
+  = help: feat: add a feature"
+    );
+}
+
+#[test]
+fn parse_scopes() {
+    let msg = "feat(api-client): add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(msg.scopes, ["api-client"]);
+
+    let msg = "fix(ui/button.rs)!: fix a bug"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(msg.scopes, ["ui/button.rs"]);
+    assert!(msg.is_breaking);
+
+    let msg = "feat(core, cli): add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(msg.scopes, ["core", "cli"]);
+    assert_eq!(msg.to_string(), "feat(core,cli): add a feature");
+}
+
+#[test]
+fn parse_scopes_invalid() {
+    let err = "feat(core,): add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidScope);
+    assert_eq!(err.span, Span::new(1, 11, 1));
+
+    let err = "feat(core,c@li): add a feature"
+        .parse::<ConvcoMessage>()
+        .unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidScope);
+    assert_eq!(err.span, Span::new(1, 12, 1));
+    assert_eq!(err.token, "@");
+
+    let opts = ParseOptions {
+        scope_chars: "_".to_string(),
+        ..Default::default()
+    };
+    let err = ConvcoMessage::parse_with("feat(api-client): add a feature", &opts).unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::InvalidScope);
+    assert_eq!(err.span, Span::new(1, 9, 1));
+}
+
+#[test]
+fn parse_footer_repeated() {
+    let msg = "feat: add a feature
+
+Co-authored-by: A <a@example.com>
+Co-authored-by: B <b@example.com>"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    let footer = msg.footer.unwrap();
+    assert_eq!(footer.len(), 2);
+    assert_eq!(
+        footer.get_all("Co-authored-by").collect::<Vec<_>>(),
+        ["A <a@example.com>", "B <b@example.com>"]
+    );
+}
+
+#[test]
+fn parse_footer_multiline() {
+    let msg = "feat: add a feature
+
+BREAKING CHANGE: the config file format changed,
+  and the old format is not supported anymore
+Refs: #123
+  #456"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert!(msg.body.is_none());
+    assert!(msg.is_breaking_change());
+    let footer = msg.footer.unwrap();
+    assert_eq!(footer.len(), 2);
+    assert_eq!(
+        footer.get("BREAKING CHANGE").unwrap(),
+        "the config file format changed,\n  and the old format is not supported anymore"
+    );
+    assert_eq!(footer.get("Refs").unwrap(), "#123\n  #456");
+}
+
+#[test]
+fn parse_footer_lenient_invalid_line() {
+    let (msg, warnings) = ConvcoMessage::parse_lenient(
+        "feat: add a feature\n\nRefs: #123\nReviewed by: Z",
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        msg.footer.unwrap().get("Refs").unwrap(),
+        "#123\nReviewed by: Z"
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, ConvcoErrorKind::InvalidFooterToken);
+}
+
+#[test]
+fn parse_footer_indented_first_line() {
+    let msg = "feat: add a feature\n\n\tRefs: #123"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(msg.body.unwrap(), "\tRefs: #123");
+    assert!(msg.footer.is_none());
+}
+
+#[test]
+fn parse_footer_hash_prose() {
+    let msg = "feat: add a feature\n\nSee #45 for details\nmore text"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(msg.body.unwrap(), "See #45 for details\nmore text");
+    assert!(msg.footer.is_none());
+
+    let msg = "feat: add a feature\n\nFixes #45\nand some more text"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(msg.body.unwrap(), "Fixes #45\nand some more text");
+    assert!(msg.footer.is_none());
+}
+
+#[test]
+fn parse_footer_last_paragraph() {
+    let msg = "feat: add a feature
+
+Note: this paragraph is part of the body
+
+Some more body"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert_eq!(
+        msg.body.unwrap(),
+        "Note: this paragraph is part of the body\n\nSome more body"
+    );
+    assert!(msg.footer.is_none());
+}
+
+#[test]
+fn parse_footer_hash() {
+    let msg = "fix: fix a bug
+
+Closes #123
+Refs: #45"
+        .parse::<ConvcoMessage>()
+        .unwrap();
+    assert!(msg.body.is_none());
+    let footer = msg.footer.unwrap();
+    assert_eq!(
+        footer.trailers,
+        [
+            Trailer::with_separator("Closes", Separator::Hash, "123"),
+            Trailer::new("Refs", "#45"),
+        ]
+    );
+}
+
+#[test]
+fn parse_lenient_valid() {
+    let (msg, warnings) =
+        ConvcoMessage::parse_lenient("feat(cli): add a feature", &ParseOptions::default()).unwrap();
+    assert_eq!(msg.r#type, "feat");
+    assert_eq!(msg.scopes, ["cli"]);
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn parse_lenient_recovered() {
+    let opts = ParseOptions {
+        types: vec!["feat".to_string(), "fix".to_string()],
+        ..Default::default()
+    };
+    let (msg, warnings) =
+        ConvcoMessage::parse_lenient("Feat(cli)!:Add a feature\nSome body", &opts).unwrap();
+    assert_eq!(msg.r#type, "feat");
+    assert_eq!(msg.scopes, ["cli"]);
+    assert!(msg.is_breaking);
+    assert_eq!(msg.desc, "Add a feature");
+    assert_eq!(msg.body.as_deref(), Some("Some body"));
+    let kinds = warnings.iter().map(|w| w.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            ConvcoErrorKind::MissingSeparator,
+            ConvcoErrorKind::TypeCase,
+            ConvcoErrorKind::DescriptionCase,
+            ConvcoErrorKind::MissingBlankLine,
+        ]
+    );
+    assert_eq!(warnings[1].span, Span::new(1, 1, 4));
+    assert_eq!(warnings[2].span, Span::new(1, 12, 1));
+
+    // strict parsing fails
+    assert!(ConvcoMessage::parse_with("Feat(cli)!:Add a feature\nSome body", &opts).is_err());
+}
+
+#[test]
+fn parse_lenient_unknown_type() {
+    let opts = ParseOptions {
+        types: vec!["feat".to_string(), "fix".to_string()],
+        ..Default::default()
+    };
+    let (msg, warnings) = ConvcoMessage::parse_lenient("feature(a b): add", &opts).unwrap();
+    assert_eq!(msg.r#type, "feature");
+    assert_eq!(msg.scopes, ["a b"]);
+    let kinds = warnings.iter().map(|w| w.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [ConvcoErrorKind::InvalidScope, ConvcoErrorKind::UnknownType]
+    );
+}
+
+#[test]
+fn parse_lenient_error() {
+    let err = ConvcoMessage::parse_lenient("add a feature", &ParseOptions::default()).unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingSeparator);
+    let err = ConvcoMessage::parse_lenient("feat: ", &ParseOptions::default()).unwrap_err();
+    assert_eq!(err.kind, ConvcoErrorKind::MissingDescription);
+}
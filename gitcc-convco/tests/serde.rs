@@ -0,0 +1,21 @@
+//! Tests for the serialization
+
+use gitcc_convco::ConvcoMessage;
+
+#[test]
+fn serde_roundtrip() {
+    let raw = "feat(cli,core)!: add a feature
+
+Closes #123
+Co-authored-by: A <a@example.com>";
+    let msg = raw.parse::<ConvcoMessage>().unwrap();
+    let json = serde_json::to_value(&msg).unwrap();
+    assert_eq!(json["type"], "feat");
+    assert_eq!(json["scopes"], serde_json::json!(["cli", "core"]));
+    assert_eq!(json["is_breaking"], true);
+    assert_eq!(json["footer"][0]["key"], "Closes");
+    assert_eq!(json["footer"][0]["separator"], "hash");
+
+    let msg: ConvcoMessage = serde_json::from_value(json).unwrap();
+    assert_eq!(msg.to_string(), raw);
+}
@@ -0,0 +1,772 @@
+//! Version bumps
+//!
+//! The version files are edited in place, preserving their formatting.
+
+use std::{
+    collections::HashSet,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use globset::Glob;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use toml_edit::{Document, Item, Value};
+
+use crate::{Error, GLOB_CHARS};
+
+/// Placeholder of the version in a version pattern
+const VERSION_PLACEHOLDER: &str = "{{version}}";
+
+/// Regex of a semver version, for the patterns with a placeholder
+const VERSION_REGEX: &str = r"\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?";
+
+/// Dependency tables of a Cargo manifest
+const CARGO_DEPS_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Version file configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BumpFile {
+    /// File path, relative to the repo root
+    pub path: PathBuf,
+    /// File format (by default, inferred from the file name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<BumpFormat>,
+    /// Version pattern (regex format only)
+    ///
+    /// The pattern is either a regex with a `version` group (eg. `VERSION = "(?P<version>[^"]+)"`),
+    /// or a text with a `{{version}}` placeholder (eg. `VERSION = "{{version}}"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// Format of a version file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BumpFormat {
+    /// Cargo manifest (`Cargo.toml`)
+    Cargo,
+    /// Cargo lock file (`Cargo.lock`)
+    CargoLock,
+    /// NPM manifest (`package.json`)
+    PackageJson,
+    /// Python project (`pyproject.toml`)
+    Pyproject,
+    /// Any file, with a version pattern
+    Regex,
+}
+
+impl BumpFile {
+    /// Returns the file format
+    pub fn format(&self) -> Result<BumpFormat, Error> {
+        if let Some(format) = self.format {
+            return Ok(format);
+        }
+        if self.pattern.is_some() {
+            return Ok(BumpFormat::Regex);
+        }
+        match self.path.file_name().and_then(|n| n.to_str()) {
+            Some("Cargo.toml") => Ok(BumpFormat::Cargo),
+            Some("Cargo.lock") => Ok(BumpFormat::CargoLock),
+            Some("package.json") => Ok(BumpFormat::PackageJson),
+            Some("pyproject.toml") => Ok(BumpFormat::Pyproject),
+            _ => Err(Error::msg(
+                format!(
+                    "unknown format for the version file '{}' (set its format or pattern)",
+                    self.path.display()
+                )
+                .as_str(),
+            )),
+        }
+    }
+}
+
+/// Bump of a version file
+#[derive(Debug, Clone)]
+pub struct FileBump {
+    /// File path, relative to the repo root
+    pub path: PathBuf,
+    /// Content before the bump
+    pub old_content: String,
+    /// Content after the bump
+    pub new_content: String,
+}
+
+impl FileBump {
+    /// Checks if the bump changes the file
+    pub fn is_changed(&self) -> bool {
+        self.old_content != self.new_content
+    }
+
+    /// Returns the diff of the changed lines
+    pub fn diff(&self) -> String {
+        let path = self.path.display();
+        let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+        let old_lines = self.old_content.lines().collect::<Vec<_>>();
+        let new_lines = self.new_content.lines().collect::<Vec<_>>();
+        if old_lines.len() != new_lines.len() {
+            // NB: a bump replaces the versions inline, so this should not happen
+            old_lines
+                .iter()
+                .for_each(|l| diff.push_str(&format!("-{l}\n")));
+            new_lines
+                .iter()
+                .for_each(|l| diff.push_str(&format!("+{l}\n")));
+            return diff;
+        }
+        for (i, (old, new)) in old_lines.iter().zip(&new_lines).enumerate() {
+            if old != new {
+                let n = i + 1;
+                diff.push_str(&format!("@@ -{n} +{n} @@\n-{old}\n+{new}\n"));
+            }
+        }
+        diff
+    }
+}
+
+/// Bumps the version files, without writing them
+///
+/// The Cargo path dependencies and the `Cargo.lock` packages are bumped
+/// for the crates whose version is bumped (including the members inheriting the workspace version).
+pub fn bump_files(
+    root_dir: &Path,
+    files: &[BumpFile],
+    version: &str,
+) -> Result<Vec<FileBump>, Error> {
+    let mut sources = vec![];
+    for file in files {
+        let format = file.format()?;
+        let content = fs::read_to_string(root_dir.join(&file.path)).map_err(|err| {
+            Error::msg(format!("failed to read '{}': {err}", file.path.display()).as_str())
+        })?;
+        sources.push((file, format, content));
+    }
+
+    // find the bumped crates
+    let mut crates = HashSet::new();
+    for (file, format, content) in &sources {
+        if *format == BumpFormat::Cargo {
+            let doc = parse_toml(&file.path, content)?;
+            let dir = root_dir.join(&file.path);
+            let dir = dir.parent().unwrap_or(root_dir);
+            crates.extend(cargo_bumped_crates(dir, &doc)?);
+        }
+    }
+
+    sources
+        .into_iter()
+        .map(|(file, format, old_content)| {
+            let path = &file.path;
+            let new_content = match format {
+                BumpFormat::Cargo => bump_cargo_manifest(path, &old_content, version, &crates)?,
+                BumpFormat::CargoLock => bump_cargo_lock(path, &old_content, version, &crates)?,
+                BumpFormat::PackageJson => bump_package_json(path, &old_content, version)?,
+                BumpFormat::Pyproject => bump_pyproject(path, &old_content, version)?,
+                BumpFormat::Regex => {
+                    let pattern = file.pattern.as_deref().ok_or_else(|| {
+                        Error::msg(
+                            format!("missing pattern for the version file '{}'", path.display())
+                                .as_str(),
+                        )
+                    })?;
+                    bump_pattern(path, &old_content, pattern, version)?
+                }
+            };
+            Ok(FileBump {
+                path: path.clone(),
+                old_content,
+                new_content,
+            })
+        })
+        .collect()
+}
+
+/// Parses a TOML file
+fn parse_toml(path: &Path, content: &str) -> Result<Document, Error> {
+    content
+        .parse::<Document>()
+        .map_err(|err| Error::msg(format!("failed to parse '{}': {err}", path.display()).as_str()))
+}
+
+/// Returns the error of a file without a version
+fn no_version_error(path: &Path) -> Error {
+    Error::msg(format!("no version found in '{}'", path.display()).as_str())
+}
+
+/// Sets a version value, preserving its decoration (whitespaces and comments)
+///
+/// Returns false if the item is not a string value (eg. `version.workspace = true`).
+fn set_version(item: &mut Item, version: &str) -> bool {
+    match item.as_value_mut() {
+        Some(value) if value.is_str() => {
+            let decor = value.decor().clone();
+            *value = Value::from(version);
+            *value.decor_mut() = decor;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Sets the version of a dependency requirement, preserving its operator and precision
+///
+/// For instance, bumping `^0.5` to `0.6.0` gives `^0.6`.
+/// A pre-release is always set in full (eg. `^0.7.0-rc.1`), since a partial requirement does not match it.
+/// The requirements with several comparators or wildcards (eg. `>=0.5, <0.7`) are not bumped.
+fn set_requirement(item: &mut Item, version: &str) -> bool {
+    let Some(req) = item.as_str() else {
+        return false;
+    };
+    let Some(req) = bump_requirement(req, version) else {
+        return false;
+    };
+    set_version(item, &req)
+}
+
+/// Returns the bumped requirement, replacing the numeric part of a single comparator
+fn bump_requirement(req: &str, version: &str) -> Option<String> {
+    if req.contains([',', '*', 'x', 'X']) {
+        return None;
+    }
+    let start = req.find(|c: char| c.is_ascii_digit())?;
+    let (op, old_version) = req.split_at(start);
+    let precision = old_version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .count();
+    let is_prerelease = version.split('+').next().unwrap_or_default().contains('-');
+    let new_version = if precision >= 3 || is_prerelease {
+        version.to_string()
+    } else {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .take(precision)
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+    Some(format!("{op}{new_version}"))
+}
+
+/// Checks if a Cargo manifest field is inherited from the workspace (eg. `version.workspace = true`)
+fn is_workspace_inherited(item: &Item) -> bool {
+    item.get("workspace").and_then(|w| w.as_bool()) == Some(true)
+}
+
+/// Returns the crates whose version is bumped with a Cargo manifest
+fn cargo_bumped_crates(dir: &Path, doc: &Document) -> Result<Vec<String>, Error> {
+    let mut crates = vec![];
+    if let Some(package) = doc.get("package") {
+        let name = package.get("name").and_then(|n| n.as_str());
+        let is_version = package.get("version").is_some_and(|v| v.is_str());
+        if let (Some(name), true) = (name, is_version) {
+            crates.push(name.to_string());
+        }
+    }
+
+    // the members inheriting the workspace version are bumped with the workspace
+    let Some(workspace) = doc.get("workspace") else {
+        return Ok(crates);
+    };
+    let is_workspace_version = workspace
+        .get("package")
+        .and_then(|p| p.get("version"))
+        .is_some_and(|v| v.is_str());
+    if !is_workspace_version {
+        return Ok(crates);
+    }
+    let patterns = |key: &str| {
+        workspace
+            .get(key)
+            .and_then(|m| m.as_array())
+            .map(|a| a.iter().filter_map(|m| m.as_str()).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    let members = expand_members(dir, &patterns("members"))?;
+    let excluded = expand_members(dir, &patterns("exclude"))?;
+    let root = dir.to_path_buf();
+    for member in members.iter().chain([&root]) {
+        if excluded.contains(member) {
+            continue;
+        }
+        let manifest_path = member.join("Cargo.toml");
+        let Ok(content) = fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+        let manifest = parse_toml(&manifest_path, &content)?;
+        let Some(package) = manifest.get("package") else {
+            continue;
+        };
+        let is_inherited = package.get("version").is_some_and(is_workspace_inherited);
+        if let (Some(name), true) = (package.get("name").and_then(|n| n.as_str()), is_inherited) {
+            crates.push(name.to_string());
+        }
+    }
+    Ok(crates)
+}
+
+/// Expands the workspace members (paths or globs) to directories
+fn expand_members(dir: &Path, patterns: &[&str]) -> Result<Vec<PathBuf>, Error> {
+    let mut members = vec![];
+    for pattern in patterns {
+        if !pattern.contains(GLOB_CHARS) {
+            members.push(dir.join(pattern));
+            continue;
+        }
+        let matcher = Glob::new(pattern)
+            .map_err(|err| Error::msg(&err.to_string()))?
+            .compile_matcher();
+        // NB: the glob is matched against the directories with as many components as the glob
+        let depth = Path::new(pattern).components().count();
+        let mut dirs = vec![(dir.to_path_buf(), 0)];
+        while let Some((d, d_depth)) = dirs.pop() {
+            if d_depth == depth {
+                if d.strip_prefix(dir).is_ok_and(|p| matcher.is_match(p)) {
+                    members.push(d);
+                }
+                continue;
+            }
+            for entry in fs::read_dir(&d)? {
+                let path = entry?.path();
+                let is_hidden = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('.') || n == "target");
+                if path.is_dir() && !is_hidden {
+                    dirs.push((path, d_depth + 1));
+                }
+            }
+        }
+    }
+    Ok(members)
+}
+
+/// Bumps the path dependencies of the bumped crates
+fn bump_path_deps(deps: &mut Item, version: &str, crates: &HashSet<String>) -> bool {
+    let Some(deps) = deps.as_table_like_mut() else {
+        return false;
+    };
+    let mut is_bumped = false;
+    for (key, dep) in deps.iter_mut() {
+        let name = dep
+            .get("package")
+            .and_then(|p| p.as_str())
+            .unwrap_or(key.get());
+        if dep.get("path").is_none() || !crates.contains(name) {
+            continue;
+        }
+        if let Some(v) = dep.get_mut("version") {
+            is_bumped |= set_requirement(v, version);
+        }
+    }
+    is_bumped
+}
+
+/// Bumps a Cargo manifest
+///
+/// The package version, the workspace version, and the versions of the internal path dependencies are bumped.
+fn bump_cargo_manifest(
+    path: &Path,
+    content: &str,
+    version: &str,
+    crates: &HashSet<String>,
+) -> Result<String, Error> {
+    let mut doc = parse_toml(path, content)?;
+    let mut is_bumped = false;
+    if let Some(v) = doc.get_mut("package").and_then(|p| p.get_mut("version")) {
+        is_bumped |= set_version(v, version);
+    }
+    if let Some(v) = doc
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("package"))
+        .and_then(|p| p.get_mut("version"))
+    {
+        is_bumped |= set_version(v, version);
+    }
+    for table in CARGO_DEPS_TABLES {
+        if let Some(deps) = doc.get_mut(table) {
+            is_bumped |= bump_path_deps(deps, version, crates);
+        }
+    }
+    if let Some(deps) = doc
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("dependencies"))
+    {
+        is_bumped |= bump_path_deps(deps, version, crates);
+    }
+    if let Some(targets) = doc.get_mut("target").and_then(|t| t.as_table_like_mut()) {
+        for (_, target) in targets.iter_mut() {
+            for table in CARGO_DEPS_TABLES {
+                if let Some(deps) = target.get_mut(table) {
+                    is_bumped |= bump_path_deps(deps, version, crates);
+                }
+            }
+        }
+    }
+    if !is_bumped {
+        return Err(no_version_error(path));
+    }
+    Ok(doc.to_string())
+}
+
+/// Bumps the local packages of a `Cargo.lock`
+fn bump_cargo_lock(
+    path: &Path,
+    content: &str,
+    version: &str,
+    crates: &HashSet<String>,
+) -> Result<String, Error> {
+    let mut doc = parse_toml(path, content)?;
+    let mut is_bumped = false;
+    if let Some(packages) = doc
+        .get_mut("package")
+        .and_then(|p| p.as_array_of_tables_mut())
+    {
+        for package in packages.iter_mut() {
+            // NB: the registry and git packages have a source
+            let is_local = package.get("source").is_none();
+            let is_bumped_crate = package
+                .get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|n| crates.contains(n));
+            if !is_local || !is_bumped_crate {
+                continue;
+            }
+            if let Some(v) = package.get_mut("version") {
+                is_bumped |= set_version(v, version);
+            }
+        }
+    }
+    if !is_bumped {
+        return Err(Error::msg(
+            format!("no bumped package found in '{}'", path.display()).as_str(),
+        ));
+    }
+    Ok(doc.to_string())
+}
+
+/// Bumps a `pyproject.toml` (PEP 621 or Poetry)
+fn bump_pyproject(path: &Path, content: &str, version: &str) -> Result<String, Error> {
+    let mut doc = parse_toml(path, content)?;
+    let mut is_bumped = false;
+    if let Some(v) = doc.get_mut("project").and_then(|p| p.get_mut("version")) {
+        is_bumped |= set_version(v, version);
+    }
+    if let Some(v) = doc
+        .get_mut("tool")
+        .and_then(|t| t.get_mut("poetry"))
+        .and_then(|p| p.get_mut("version"))
+    {
+        is_bumped |= set_version(v, version);
+    }
+    if !is_bumped {
+        return Err(no_version_error(path));
+    }
+    Ok(doc.to_string())
+}
+
+/// Bumps the top-level `version` of a `package.json`
+fn bump_package_json(path: &Path, content: &str, version: &str) -> Result<String, Error> {
+    let span = find_json_version(content).ok_or_else(|| no_version_error(path))?;
+    Ok(format!(
+        "{}{}{}",
+        &content[..span.start],
+        version,
+        &content[span.end..]
+    ))
+}
+
+/// Finds the top-level `version` string of a JSON object (span without the quotes)
+fn find_json_version(json: &str) -> Option<Range<usize>> {
+    let bytes = json.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            b'"' => {
+                let start = i + 1;
+                let end = find_json_string_end(bytes, start)?;
+                i = end;
+                if depth == 1 && &json[start..end] == "version" {
+                    let value = json[end + 1..].trim_start();
+                    if let Some(value) = value.strip_prefix(':').map(|v| v.trim_start()) {
+                        if value.starts_with('"') {
+                            let value_start = json.len() - value.len() + 1;
+                            let value_end = find_json_string_end(bytes, value_start)?;
+                            return Some(value_start..value_end);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Finds the closing quote of a JSON string
+fn find_json_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns the regex of a version pattern
+fn pattern_regex(pattern: &str) -> Result<Regex, Error> {
+    let regex = if pattern.contains(VERSION_PLACEHOLDER) {
+        pattern
+            .split(VERSION_PLACEHOLDER)
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(&format!("(?P<version>{VERSION_REGEX})"))
+    } else {
+        pattern.to_string()
+    };
+    let regex = Regex::new(&regex).map_err(|err| {
+        Error::msg(format!("invalid version pattern '{pattern}': {err}").as_str())
+    })?;
+    if !regex.capture_names().any(|n| n == Some("version")) {
+        return Err(Error::msg(
+            format!("the version pattern '{pattern}' has no 'version' group or placeholder")
+                .as_str(),
+        ));
+    }
+    Ok(regex)
+}
+
+/// Bumps all the versions matching a pattern
+fn bump_pattern(path: &Path, content: &str, pattern: &str, version: &str) -> Result<String, Error> {
+    let regex = pattern_regex(pattern)?;
+    if !regex.is_match(content) {
+        return Err(no_version_error(path));
+    }
+    let bumped = regex.replace_all(content, |caps: &Captures| {
+        let all = caps.get(0).unwrap();
+        let v = caps.name("version").unwrap();
+        format!(
+            "{}{}{}",
+            &content[all.start()..v.start()],
+            version,
+            &content[v.end()..all.end()]
+        )
+    });
+    Ok(bumped.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump_file(path: &str) -> BumpFile {
+        BumpFile {
+            path: PathBuf::from(path),
+            format: None,
+            pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_bump_cargo_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let file = dir.path().join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            r#"[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "0.5.0" # shared version
+
+[workspace.dependencies]
+foo = { path = "crates/foo", version = "0.5.0" }
+serde = "1.0.0"
+"#,
+        );
+        write(
+            "crates/foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion.workspace = true\n",
+        );
+        write(
+            "crates/bar/Cargo.toml",
+            r#"[package]
+name = "bar"
+version = "0.1.0"
+
+[dependencies]
+foo = { path = "../foo", version = "0.5.0" }
+other = { path = "../other", version = "0.2.0" }
+
+[target.'cfg(unix)'.dev-dependencies]
+foo-alias = { package = "foo", path = "../foo", version = "0.5" }
+
+[build-dependencies]
+foo = { path = "../foo", version = "=0.5.0" }
+"#,
+        );
+        write(
+            "Cargo.lock",
+            r#"version = 3
+
+[[package]]
+name = "bar"
+version = "0.1.0"
+
+[[package]]
+name = "foo"
+version = "0.5.0"
+
+[[package]]
+name = "serde"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        );
+
+        let files = [bump_file("Cargo.toml"), bump_file("Cargo.lock")];
+        let bumps = bump_files(dir.path(), &files, "0.6.0").unwrap();
+        assert_eq!(
+            bumps[0].new_content,
+            r#"[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "0.6.0" # shared version
+
+[workspace.dependencies]
+foo = { path = "crates/foo", version = "0.6.0" }
+serde = "1.0.0"
+"#
+        );
+        assert_eq!(
+            bumps[0].diff(),
+            r#"--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -5 +5 @@
+-version = "0.5.0" # shared version
++version = "0.6.0" # shared version
+@@ -8 +8 @@
+-foo = { path = "crates/foo", version = "0.5.0" }
++foo = { path = "crates/foo", version = "0.6.0" }
+"#
+        );
+        assert!(bumps[1]
+            .new_content
+            .contains("name = \"foo\"\nversion = \"0.6.0\""));
+        assert!(bumps[1]
+            .new_content
+            .contains("name = \"bar\"\nversion = \"0.1.0\""));
+        assert!(bumps[1]
+            .new_content
+            .contains("name = \"serde\"\nversion = \"1.0.0\""));
+
+        let files = [bump_file("crates/bar/Cargo.toml"), bump_file("Cargo.toml")];
+        let bumps = bump_files(dir.path(), &files, "0.6.0").unwrap();
+        let bar = &bumps[0].new_content;
+        assert!(bar.contains("version = \"0.6.0\"\n\n[dependencies]"));
+        assert!(bar.contains("foo = { path = \"../foo\", version = \"0.6.0\" }"));
+        assert!(bar.contains("other = { path = \"../other\", version = \"0.2.0\" }"));
+        assert!(bar.contains("package = \"foo\", path = \"../foo\", version = \"0.6\" }"));
+        assert!(bar.contains("foo = { path = \"../foo\", version = \"=0.6.0\" }"));
+
+        let err = bump_files(dir.path(), &[bump_file("crates/foo/Cargo.toml")], "0.6.0");
+        assert!(err.is_err());
+
+        // the Cargo.lock is only bumped with the bumped crates
+        let err = bump_files(dir.path(), &[bump_file("Cargo.lock")], "0.6.0");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_bump_requirement() {
+        assert_eq!(bump_requirement("0.5", "0.6.0").as_deref(), Some("0.6"));
+        assert_eq!(bump_requirement("0.5.0", "0.6.0").as_deref(), Some("0.6.0"));
+        assert_eq!(
+            bump_requirement("=0.5.0", "0.6.0").as_deref(),
+            Some("=0.6.0")
+        );
+        assert_eq!(bump_requirement("^0.5", "0.6.0").as_deref(), Some("^0.6"));
+        assert_eq!(bump_requirement("~1", "2.0.0").as_deref(), Some("~2"));
+        assert_eq!(
+            bump_requirement("^0.5", "0.7.0-rc.1").as_deref(),
+            Some("^0.7.0-rc.1")
+        );
+        assert_eq!(
+            bump_requirement("=0.5.0", "0.7.0-rc.1").as_deref(),
+            Some("=0.7.0-rc.1")
+        );
+        assert_eq!(
+            bump_requirement(">= 1.0.0", "2.0.0-rc.1").as_deref(),
+            Some(">= 2.0.0-rc.1")
+        );
+        assert_eq!(bump_requirement(">=0.5, <0.7", "0.6.0"), None);
+        assert_eq!(bump_requirement("0.*", "0.6.0"), None);
+    }
+
+    #[test]
+    fn test_bump_package_json() {
+        let json = r#"{
+  "name": "foo",
+  "dependencies": { "version": "1.0.0" },
+  "description": "a \"version\"",
+  "version":  "1.2.3",
+  "scripts": {}
+}"#;
+        let bumped = bump_package_json(Path::new("package.json"), json, "1.3.0").unwrap();
+        assert_eq!(bumped, json.replace("1.2.3", "1.3.0"));
+        assert!(bump_package_json(Path::new("package.json"), "{}", "1.3.0").is_err());
+    }
+
+    #[test]
+    fn test_bump_pyproject() {
+        let toml =
+            "[project]\nname = \"foo\"\nversion = '1.2.3'\n\n[tool.poetry]\nversion = \"1.2.3\"\n";
+        let bumped = bump_pyproject(Path::new("pyproject.toml"), toml, "2.0.0").unwrap();
+        assert_eq!(
+            bumped,
+            "[project]\nname = \"foo\"\nversion = \"2.0.0\"\n\n[tool.poetry]\nversion = \"2.0.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_bump_pattern() {
+        let content = "VERSION = \"1.2.3\"\n# version 1.2.3\n";
+        let path = Path::new("version.py");
+        let bumped = bump_pattern(path, content, "VERSION = \"{{version}}\"", "1.3.0").unwrap();
+        assert_eq!(bumped, "VERSION = \"1.3.0\"\n# version 1.2.3\n");
+        let bumped = bump_pattern(path, content, r"version (?P<version>\S+)", "1.3.0").unwrap();
+        assert_eq!(bumped, "VERSION = \"1.2.3\"\n# version 1.3.0\n");
+        assert!(bump_pattern(path, content, "VERSION", "1.3.0").is_err());
+        assert!(bump_pattern(path, content, "APP_VERSION={{version}}", "1.3.0").is_err());
+    }
+
+    #[test]
+    fn test_bump_format() {
+        assert_eq!(
+            bump_file("crates/foo/Cargo.toml").format().unwrap(),
+            BumpFormat::Cargo
+        );
+        assert_eq!(
+            bump_file("web/package.json").format().unwrap(),
+            BumpFormat::PackageJson
+        );
+        assert!(bump_file("VERSION").format().is_err());
+    }
+}
@@ -60,7 +60,7 @@ pub struct ChangelogBuildOptions {
     pub origin_name: Option<String>,
     /// Includes all commits
     pub all: bool,
-    /// Next version
+    /// Next version (the unreleased commits are labelled 'Unreleased' if not set)
     pub next_version: Option<String>,
 }
 
@@ -89,6 +89,12 @@ pub fn build_changelog(
         .group_by(|c| c.version_tag.clone())
         .into_iter()
     {
+        // NB: the unreleased commits are skipped if there is nothing to release (eg. only merge commits)
+        let is_unreleased = release_tag.is_none();
+        if is_unreleased && history.next_version.is_none() && opts.next_version.is_none() {
+            continue;
+        }
+
         // eprintln!(
         //     "RELEASE: {}",
         //     release_tag
@@ -109,6 +115,7 @@ pub fn build_changelog(
         for c in release_commits {
             // eprintln!("{}", c.subject());
             let c_sect_label = match &c.conv_message {
+                _ if c.ignored => HIDDEN.to_string(),
                 Some(m) => {
                     // eprint!("=> is conventional: {}", m.r#type);
                     match cfg.changelog.find_section_for_commit_type(&m.r#type) {
@@ -151,8 +158,9 @@ pub fn build_changelog(
             });
         let release_date = release_tag
             .as_ref()
-            .map(|t| t.date)
+            .map(|t| t.release_date())
             .unwrap_or(OffsetDateTime::now_utc());
+        let release_notes = release_tag.as_ref().and_then(|t| t.message.clone());
         let release_url = release_tag
             .as_ref()
             .map(|t| build_release_url(&origin_url, &t.name));
@@ -161,6 +169,7 @@ pub fn build_changelog(
             version: release_version,
             date: release_date,
             url: release_url,
+            notes: release_notes,
             sections,
         };
         releases.push(release);
@@ -192,7 +201,7 @@ fn commit_oneliner(origin_url: &str, commit: &Commit) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::commit_history;
+    use crate::{commit_history, test_utils, CommitHistoryOptions};
 
     use super::*;
 
@@ -200,10 +209,74 @@ mod tests {
     fn test_changelog() {
         let cwd = std::env::current_dir().unwrap();
         let cfg = Config::load_from_fs(&cwd).unwrap().unwrap_or_default();
-        let history = commit_history(&cwd, &cfg).unwrap();
+        let history = commit_history(&cwd, &cfg, None).unwrap();
         let _changelog = build_changelog(&cwd, &cfg, &history, None).unwrap();
         // eprintln!("{:#?}", changelog);
         // let changelog_str = changelog.generate(TEMPLATE_CHANGELOG_STD).unwrap();
         // eprintln!("{}", changelog_str);
     }
+
+    #[test]
+    fn test_changelog_nothing_to_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        repo.remote("origin", "https://github.com/nlargueze/gitcc")
+            .unwrap();
+        let cfg = Config::default();
+
+        let base_id = test_utils::commit(&repo, "feat: add a feature");
+        gitcc_git::set_annotated_tag(&repo, "v1.1.0", "Release v1.1.0").unwrap();
+        test_utils::commit(&repo, "fixup! feat: add a feature");
+        test_utils::commit_with_parents(&repo, "Merge branch 'other'", &[&base_id]);
+
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.next_version, None);
+        assert_eq!(history.next_version_str(), None);
+
+        let opts = ChangelogBuildOptions {
+            next_version: history.next_version_str(),
+            ..Default::default()
+        };
+        let changelog = build_changelog(dir.path(), &cfg, &history, Some(opts)).unwrap();
+        let versions = changelog
+            .releases
+            .iter()
+            .map(|r| r.version.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, ["v1.1.0"]);
+    }
+
+    #[test]
+    fn test_changelog_from_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        repo.remote("origin", "https://github.com/nlargueze/gitcc")
+            .unwrap();
+        let cfg = Config::default();
+
+        test_utils::commit(&repo, "feat: add a feature");
+        gitcc_git::set_annotated_tag(&repo, "v1.0.0", "Release v1.0.0").unwrap();
+        test_utils::commit(&repo, "feat: add another feature");
+
+        // NB: the range start is excluded, so the version is read from the tags reachable from it
+        let opts = CommitHistoryOptions {
+            range: Some("v1.0.0..HEAD".to_string()),
+            ..Default::default()
+        };
+        let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+        assert_eq!(history.commits.len(), 1);
+        assert_eq!(history.curr_version, Some(semver::Version::new(1, 0, 0)));
+        assert_eq!(history.next_version_str().as_deref(), Some("v1.1.0"));
+
+        let opts = ChangelogBuildOptions {
+            next_version: history.next_version_str(),
+            ..Default::default()
+        };
+        let changelog = build_changelog(dir.path(), &cfg, &history, Some(opts)).unwrap();
+        let changelog_str = changelog
+            .render(gitcc_changelog::TEMPLATE_CHANGELOG_STD)
+            .unwrap();
+        assert!(changelog_str.contains("## [v1.1.0]"));
+        assert!(!changelog_str.contains("v0.1.0"));
+    }
 }
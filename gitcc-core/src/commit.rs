@@ -7,21 +7,36 @@ use std::{
     path::Path,
 };
 
-use gitcc_convco::{ConvcoMessage, DEFAULT_CONVCO_INCR_MINOR_TYPES, DEFAULT_CONVCO_TYPES};
-use gitcc_git::discover_repo;
-use semver::Version;
+use gitcc_convco::{
+    revert_target, CommitKind, ConvcoError, ConvcoMessage, ParseOptions, RevertTarget,
+    DEFAULT_CONVCO_INCR_MINOR_TYPES, DEFAULT_CONVCO_TYPES, DEFAULT_SCOPE_CHARS,
+};
+use gitcc_git::{discover_repo, CommitLogOptions};
+use semver::{Prerelease, Version};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
-pub use gitcc_git::StatusShow;
+pub use gitcc_git::{LogOrder, StatusShow};
 
-use crate::{Config, Error};
+use crate::{Config, Error, PackageMatcher};
 
 /// Commits configuration
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CommitConfig {
     /// Valid commit types (key + description)
     pub types: BTreeMap<String, String>,
+    /// Characters allowed in a scope, in addition to alphanumeric characters
+    #[serde(default = "default_scope_chars")]
+    pub scope_chars: String,
+    /// Parses the commit history leniently
+    ///
+    /// Slightly malformed messages (eg. uppercase type, missing blank line) are recovered
+    /// instead of being considered as non conventional.
+    #[serde(default = "default_lenient")]
+    pub lenient: bool,
+    /// Kinds of commits ignored for versioning and in the changelog (merge, revert, fixup, squash)
+    #[serde(default = "default_ignored_kinds")]
+    pub ignored_kinds: Vec<CommitKind>,
 }
 
 impl Default for CommitConfig {
@@ -31,10 +46,38 @@ impl Default for CommitConfig {
                 .iter()
                 .map(|(k, v)| (k.to_string(), v.to_string()))
                 .collect(),
+            scope_chars: default_scope_chars(),
+            lenient: default_lenient(),
+            ignored_kinds: default_ignored_kinds(),
+        }
+    }
+}
+
+impl CommitConfig {
+    /// Returns the options to parse the conventional messages
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            scope_chars: self.scope_chars.clone(),
+            types: self.types.keys().cloned().collect(),
         }
     }
 }
 
+/// Default characters allowed in a scope
+fn default_scope_chars() -> String {
+    DEFAULT_SCOPE_CHARS.to_string()
+}
+
+/// Default lenient parsing
+fn default_lenient() -> bool {
+    true
+}
+
+/// Default ignored kinds of commits
+fn default_ignored_kinds() -> Vec<CommitKind> {
+    vec![CommitKind::Merge, CommitKind::Fixup, CommitKind::Squash]
+}
+
 /// Versioning configuration
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VersioningConfig {
@@ -42,6 +85,15 @@ pub struct VersioningConfig {
     ///
     /// Those are enhancements (vs fixes or cosmetic changes)
     pub types_incr_minor: Vec<String>,
+    /// Versioning policy for 0.x versions
+    #[serde(default)]
+    pub zero_major: ZeroMajorPolicy,
+    /// Template of the version tags (eg. `v{{version}}`, `release-{{version}}`, `mycrate@{{version}}`)
+    #[serde(default = "default_tag_template")]
+    pub tag_template: String,
+    /// Accepts lightweight tags as releases (by default, only annotated tags are releases)
+    #[serde(default)]
+    pub lightweight_tags: bool,
 }
 
 impl Default for VersioningConfig {
@@ -50,20 +102,84 @@ impl Default for VersioningConfig {
             types_incr_minor: DEFAULT_CONVCO_INCR_MINOR_TYPES
                 .map(|s| s.to_string())
                 .to_vec(),
+            zero_major: ZeroMajorPolicy::default(),
+            tag_template: default_tag_template(),
+            lightweight_tags: false,
         }
     }
 }
 
+impl VersioningConfig {
+    /// Returns the tag name of a version
+    pub fn format_tag(&self, version: &Version) -> String {
+        format_tag(&self.tag_template, version)
+    }
+
+    /// Parses the version from a tag name
+    ///
+    /// None is returned if the tag does not match the template, or is not a semver version.
+    pub fn parse_tag(&self, tag: &str) -> Option<Version> {
+        parse_tag(&self.tag_template, tag)
+    }
+}
+
+/// Placeholder of the version in a tag template
+const TAG_VERSION_PLACEHOLDER: &str = "{{version}}";
+
+/// Default tag template
+fn default_tag_template() -> String {
+    format!("v{TAG_VERSION_PLACEHOLDER}")
+}
+
+/// Returns the tag name of a version from a tag template
+fn format_tag(template: &str, version: &Version) -> String {
+    template.replace(TAG_VERSION_PLACEHOLDER, &version.to_string())
+}
+
+/// Checks if a tag name looks like a version (eg. `1.2.0`, `release/1.2.0`)
+fn looks_like_version(tag: &str) -> bool {
+    tag.find(|c: char| c.is_ascii_digit())
+        .is_some_and(|i| tag[i..].parse::<Version>().is_ok())
+}
+
+/// Parses the version from a tag name with a tag template
+fn parse_tag(template: &str, tag: &str) -> Option<Version> {
+    let (prefix, suffix) = template
+        .split_once(TAG_VERSION_PLACEHOLDER)
+        .unwrap_or((template, ""));
+    tag.trim()
+        .strip_prefix(prefix)?
+        .strip_suffix(suffix)?
+        .parse::<Version>()
+        .ok()
+}
+
+/// Versioning policy for 0.x versions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZeroMajorPolicy {
+    /// Strict semver: a breaking change bumps the major version (eg. 0.3.1 -> 1.0.0)
+    Strict,
+    /// A breaking change bumps the minor version (eg. 0.3.1 -> 0.4.0), a fix bumps the patch version
+    #[default]
+    BreakingBumpsMinor,
+    /// Initial development: breaking changes are ignored, and the increment depends on the commit type only
+    ///
+    /// eg. `feat!: ...` bumps the minor version, and `fix!: ...` bumps the patch version
+    InitialDevelopment,
+}
+
 /// A commit
 ///
 /// This commit object extends the std commit with:
-/// - its tag
+/// - its tags
 /// - the parsed conventional message
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Commit {
     /// ID (hash)
     pub id: String,
     /// Date
+    #[serde(with = "time::serde::rfc3339")]
     pub date: OffsetDateTime,
     /// Author name
     pub author_name: String,
@@ -77,9 +193,26 @@ pub struct Commit {
     pub raw_message: String,
     /// Parsed convco message (None if not a conventional message)
     pub conv_message: Option<ConvcoMessage>,
-    /// Tag object
-    pub tag: Option<gitcc_git::Tag>,
+    /// Warnings raised when the message was parsed leniently
+    pub parse_warnings: Vec<ConvcoError>,
+    /// Changed files (only listed when the history is filtered by package or paths)
+    pub files: Option<Vec<String>>,
+    /// Kind of commit
+    pub kind: CommitKind,
+    /// ID of the commit reverted by this commit (revert commits only)
+    pub reverts: Option<String>,
+    /// Indicates that the commit is ignored for versioning and in the changelog
+    ///
+    /// A commit is ignored if its kind is ignored, or if it is an unreleased commit
+    /// cancelled by an unreleased revert (the revert is ignored too).
+    pub ignored: bool,
+    /// Version increment implied by the commit (none if the commit is ignored)
+    pub version_incr: VersionIncr,
+    /// Tags (sorted by name)
+    pub tags: Vec<gitcc_git::Tag>,
     /// Version to which the commit belongs (None = unreleased)
+    ///
+    /// If a commit has several version tags, the tag with the highest version is selected.
     pub version_tag: Option<gitcc_git::Tag>,
 }
 
@@ -91,17 +224,19 @@ impl Commit {
         short_id
     }
 
-    /// Returns the commit subject (1st line)
+    /// Returns the commit subject (1st line, empty if the message is empty)
     pub fn subject(&self) -> String {
-        if let Some(line) = self.raw_message.lines().next() {
-            return line.to_string();
-        }
-        unreachable!()
+        self.raw_message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()
     }
 }
 
 /// The semver version increment
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum VersionIncr {
     None,
     Patch,
@@ -121,13 +256,28 @@ impl Display for VersionIncr {
 }
 
 impl VersionIncr {
+    /// Returns the increment between 2 versions (the prerelease is ignored)
+    pub fn between(from: &Version, to: &Version) -> Self {
+        if to.major != from.major {
+            VersionIncr::Major
+        } else if to.minor != from.minor {
+            VersionIncr::Minor
+        } else if to.patch != from.patch {
+            VersionIncr::Patch
+        } else {
+            VersionIncr::None
+        }
+    }
+
     /// Applies a version increment to a version
-    fn apply(&self, version: &Option<Version>) -> Version {
+    ///
+    /// The policy drives how 0.x versions are incremented.
+    pub fn apply(&self, version: &Option<Version>, policy: ZeroMajorPolicy) -> Version {
         if let Some(v) = version {
-            if v.major == 0 {
+            if v.major == 0 && policy != ZeroMajorPolicy::Strict {
                 match self {
                     VersionIncr::None => v.clone(),
-                    VersionIncr::Patch => Version::new(0, v.minor + 1, 0),
+                    VersionIncr::Patch => Version::new(0, v.minor, v.patch + 1),
                     VersionIncr::Minor => Version::new(0, v.minor + 1, 0),
                     VersionIncr::Major => Version::new(0, v.minor + 1, 0),
                 }
@@ -145,10 +295,38 @@ impl VersionIncr {
     }
 }
 
+/// Returns the next prerelease version of a channel (eg. `1.3.0-rc.2`)
+///
+/// The prerelease number is incremented from the released prereleases of the same version and channel.
+fn next_prerelease(
+    version: &Version,
+    channel: &str,
+    released: &[Version],
+) -> Result<Version, Error> {
+    let prefix = format!("{channel}.");
+    let last_num = released
+        .iter()
+        .filter(|v| {
+            v.major == version.major && v.minor == version.minor && v.patch == version.patch
+        })
+        .filter_map(|v| v.pre.as_str().strip_prefix(&prefix)?.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+
+    let mut next = Version::new(version.major, version.minor, version.patch);
+    next.pre = Prerelease::new(&format!("{channel}.{}", last_num + 1)).map_err(|err| {
+        Error::msg(format!("invalid prerelease channel '{channel}': {err}").as_str())
+    })?;
+    Ok(next)
+}
+
 /// Extension trait for conventional messages
 pub trait ConvcoMessageExt {
     /// Determines the kin of version increment for a conventional message
     fn version_incr_kind(&self, cfg: &VersioningConfig) -> VersionIncr;
+
+    /// Determines the kind of version increment from the commit type only (breaking changes are ignored)
+    fn type_version_incr_kind(&self, cfg: &VersioningConfig) -> VersionIncr;
 }
 
 impl ConvcoMessageExt for ConvcoMessage {
@@ -156,7 +334,10 @@ impl ConvcoMessageExt for ConvcoMessage {
         if self.is_breaking_change() {
             return VersionIncr::Major;
         }
+        self.type_version_incr_kind(cfg)
+    }
 
+    fn type_version_incr_kind(&self, cfg: &VersioningConfig) -> VersionIncr {
         if cfg.types_incr_minor.contains(&self.r#type) {
             return VersionIncr::Minor;
         }
@@ -165,21 +346,104 @@ impl ConvcoMessageExt for ConvcoMessage {
 }
 
 /// Commit history
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct CommitHistory {
     /// Commits
     ///
     /// The list is ordered with the last commit first
     pub commits: Vec<Commit>,
-    /// Current version
+    /// Current version (can be a prerelease)
     pub curr_version: Option<Version>,
-    /// Next version (unreleased)
-    pub next_version: Version,
+    /// Current stable version
+    pub stable_version: Option<Version>,
+    /// Released versions (latest first)
+    pub versions: Vec<Version>,
+    /// Next version (None if there is nothing to release)
+    ///
+    /// The version increment is computed from the last stable version, the prereleases are ignored.
+    /// There is nothing to release if no unreleased commit implies an increment (eg. only merge commits).
+    pub next_version: Option<Version>,
+    /// Template of the version tags
+    pub tag_template: String,
+    /// Tags which look like versions, but are not considered as releases
+    pub ignored_tags: Vec<IgnoredTag>,
+}
+
+/// A tag which looks like a version, but is not considered as a release
+#[derive(Debug, Clone, Serialize)]
+pub struct IgnoredTag {
+    /// Tag name
+    pub name: String,
+    /// Commit ID (hash)
+    pub commit_id: String,
+    /// Reason why the tag is ignored
+    pub reason: IgnoredTagReason,
+}
+
+/// Reason why a tag is ignored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoredTagReason {
+    /// The tag is a lightweight tag, and only annotated tags are accepted
+    Lightweight,
+    /// The tag does not match the tag template
+    TemplateMismatch {
+        /// Tag template
+        template: String,
+    },
+    /// The commit has another version tag with a higher version
+    Conflict {
+        /// Selected version tag
+        tag: String,
+    },
+}
+
+impl Display for IgnoredTagReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgnoredTagReason::Lightweight => write!(
+                f,
+                "lightweight tag (set 'version.lightweight_tags = true' to accept it)"
+            ),
+            IgnoredTagReason::TemplateMismatch { template } => {
+                write!(f, "does not match the tag template '{template}'")
+            }
+            IgnoredTagReason::Conflict { tag } => {
+                write!(
+                    f,
+                    "conflicts with tag '{tag}' on the same commit (the highest version is used)"
+                )
+            }
+        }
+    }
 }
 
 impl CommitHistory {
-    pub fn next_version_str(&self) -> String {
-        format!("v{}", self.next_version)
+    /// Returns the tag name of the next version (None if there is nothing to release)
+    pub fn next_version_str(&self) -> Option<String> {
+        self.next_version.as_ref().map(|v| self.format_tag(v))
+    }
+
+    /// Returns the tag name of a version
+    pub fn format_tag(&self, version: &Version) -> String {
+        format_tag(&self.tag_template, version)
+    }
+
+    /// Returns the next prerelease version for a channel (eg. `alpha`, `beta`, `rc`)
+    ///
+    /// eg. `1.3.0-rc.1`, then `1.3.0-rc.2` if `1.3.0-rc.1` is released
+    ///
+    /// None is returned if there is nothing to release.
+    pub fn next_prerelease_version(&self, channel: &str) -> Result<Option<Version>, Error> {
+        self.next_version
+            .as_ref()
+            .map(|v| self.prerelease_version(v, channel))
+            .transpose()
+    }
+
+    /// Returns the next prerelease of a version for a channel
+    pub fn prerelease_version(&self, version: &Version, channel: &str) -> Result<Version, Error> {
+        next_prerelease(version, channel, &self.versions)
     }
 }
 
@@ -200,27 +464,143 @@ pub fn git_add_all(cwd: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Commit history options
+#[derive(Debug, Clone, Default)]
+pub struct CommitHistoryOptions {
+    /// Package (monorepo)
+    ///
+    /// Only the commits changing the package files are included, and the versions are
+    /// read from the package tags.
+    pub package: Option<String>,
+    /// Path globs, relative to the repo root (eg. `crates/foo/**`)
+    ///
+    /// If set, only the commits changing a matching file are included.
+    pub paths: Vec<String>,
+    /// Revision range (eg. `v1.0.0..v1.1.0`)
+    ///
+    /// The versions are computed from the tags found in the range, and from the tags reachable from the range start.
+    pub range: Option<String>,
+    /// Only follows the 1st parent of merge commits
+    pub first_parent: bool,
+    /// Order of the commits (topological if the walk stops at the last stable release)
+    pub order: LogOrder,
+    /// Stops walking the history at the last stable release
+    ///
+    /// The older commits are not included, which is enough to compute the next version.
+    pub since_last_release: bool,
+}
+
 /// Returns the history of all commits
-pub fn commit_history(cwd: &Path, cfg: &Config) -> Result<CommitHistory, Error> {
+pub fn commit_history(
+    cwd: &Path,
+    cfg: &Config,
+    opts: Option<CommitHistoryOptions>,
+) -> Result<CommitHistory, Error> {
+    let opts = opts.unwrap_or_default();
+    let package = match &opts.package {
+        Some(name) => Some(cfg.package(name)?),
+        None => None,
+    };
+    let mut matchers = vec![];
+    if let Some(p) = package {
+        matchers.push(p.matcher()?);
+    }
+    if !opts.paths.is_empty() {
+        matchers.push(PackageMatcher::new(&opts.paths)?);
+    }
+    let tag_template = match package {
+        Some(p) => p.tag_template(),
+        None => cfg.version.tag_template.clone(),
+    };
+
     let repo = gitcc_git::discover_repo(cwd)?;
-    let git_commits = gitcc_git::commit_log(&repo)?;
-    let map_commit_to_tag: HashMap<_, _> = gitcc_git::get_tag_refs(&repo)?
-        .into_iter()
-        .map(|t| (t.commit_id.clone(), t))
-        .collect();
+    let mut map_commit_to_tags: HashMap<String, Vec<gitcc_git::Tag>> = HashMap::new();
+    for t in gitcc_git::get_tag_refs(&repo)? {
+        map_commit_to_tags
+            .entry(t.commit_id.clone())
+            .or_default()
+            .push(t);
+    }
+    for tags in map_commit_to_tags.values_mut() {
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    // NB: the path filters are not passed to the git log, since the tags must be read from all the commits
+    // NB: the walk can only stop at the last stable release if a commit is never listed before its children
+    let log_opts = CommitLogOptions {
+        with_files: !matchers.is_empty(),
+        range: opts.range.clone(),
+        first_parent: opts.first_parent,
+        order: if opts.since_last_release {
+            LogOrder::Topological
+        } else {
+            opts.order
+        },
+        ..Default::default()
+    };
+    // versions of the accepted version tags of a commit (highest version first)
+    let commit_versions = |commit_id: &str| {
+        let mut versions = map_commit_to_tags
+            .get(commit_id)
+            .map(|tags| {
+                tags.iter()
+                    .filter(|t| t.is_annotated() || cfg.version.lightweight_tags)
+                    .filter_map(|t| parse_tag(&tag_template, &t.name))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions
+    };
+    let is_stable_release =
+        |c: &gitcc_git::Commit| commit_versions(&c.id).iter().any(|v| v.pre.is_empty());
+    let mut git_commits = gitcc_git::commit_log_until(&repo, &log_opts, |c| {
+        opts.since_last_release && is_stable_release(c)
+    })?;
+    // NB: the unreleased commits of a merged branch can be listed after the last release commit,
+    // so the commits not reachable from the last release commit are walked again
+    if let Some(release) = git_commits.pop() {
+        if opts.since_last_release && is_stable_release(&release) {
+            let unreleased_opts = CommitLogOptions {
+                hide: vec![release.id.clone()],
+                ..log_opts.clone()
+            };
+            git_commits = gitcc_git::commit_log_with(&repo, &unreleased_opts)?;
+        }
+        git_commits.push(release);
+    }
 
     let mut commits = Vec::new();
     let mut curr_version: Option<Version> = None; // current version
+    let mut stable_version: Option<Version> = None; // current stable version
+    let mut versions = vec![];
+    let mut stable_index = None; // index of the last stable release commit
+    let mut ignored_tags = vec![];
+    let other_tag_templates = cfg
+        .packages
+        .iter()
+        .map(|p| p.tag_template())
+        .chain([cfg.version.tag_template.clone()])
+        .filter(|t| *t != tag_template)
+        .collect::<Vec<_>>();
     let mut latest_version_tag: Option<gitcc_git::Tag> = None;
-    let mut unreleased_incr_kind = VersionIncr::None; // type of increment for the next version
-    let mut is_commit_released = false;
+    let parse_opts = cfg.commit.parse_options();
     for c in git_commits {
         // NB: this loop is with the last commit first, so we walk towards the 1st commit
-        let conv_message = match c.message.parse::<ConvcoMessage>() {
-            Ok(m) => {
+        let parsed = if cfg.commit.lenient {
+            ConvcoMessage::parse_lenient(&c.message, &parse_opts)
+        } else {
+            ConvcoMessage::parse_with(&c.message, &parse_opts).map(|m| (m, vec![]))
+        };
+        let mut parse_warnings = vec![];
+        let conv_message = match parsed {
+            Ok((m, warnings)) => {
                 if !cfg.commit.types.contains_key(&m.r#type) {
                     log::debug!("commit {} has an invalid type: {}", c.id, m.r#type);
                 }
+                for w in &warnings {
+                    log::debug!("commit {} was parsed leniently: {}", c.id, w);
+                }
+                parse_warnings = warnings;
                 Some(m)
             }
             Err(err) => {
@@ -233,49 +613,98 @@ pub fn commit_history(cwd: &Path, cfg: &Config) -> Result<CommitHistory, Error>
             }
         };
 
-        let tag = map_commit_to_tag.get(&c.id).cloned();
+        let tags = map_commit_to_tags.get(&c.id).cloned().unwrap_or_default();
 
-        // if an annotated tag is found, set the commit version
-        let mut has_annotated_tag = false;
-        if let Some(tag) = &tag {
-            if tag.is_annotated() {
-                has_annotated_tag = true
-            }
-        }
-        if has_annotated_tag {
-            let tag = tag.clone().unwrap();
-            let tag_name = tag.name.trim();
-            let tag_version = tag_name.strip_prefix('v').unwrap_or(tag_name);
-            match tag_version.parse::<Version>() {
-                Ok(v) => {
-                    // eprintln!(" => version: {}", v);
-                    latest_version_tag = Some(tag);
-                    if curr_version.is_none() {
-                        curr_version = Some(v);
-                    }
-                    is_commit_released = true;
+        // find the version tags of the commit
+        let mut version_tags = vec![];
+        for tag in &tags {
+            let accepts_tag = tag.is_annotated() || cfg.version.lightweight_tags;
+            match parse_tag(&tag_template, &tag.name) {
+                Some(v) if accepts_tag => {
+                    version_tags.push((v, tag));
+                }
+                Some(_) => {
+                    ignored_tags.push(IgnoredTag {
+                        name: tag.name.clone(),
+                        commit_id: c.id.clone(),
+                        reason: IgnoredTagReason::Lightweight,
+                    });
                 }
-                Err(err) => {
+                None => {
                     log::debug!(
-                        "commit {} has tag {} which is not a semver version: {}",
+                        "commit {} has tag {} which does not match the version tag template '{}'",
                         c.id,
                         tag.name,
-                        err
+                        tag_template
                     );
+                    // NB: the tags of the other packages are expected
+                    let is_other_version_tag = other_tag_templates
+                        .iter()
+                        .any(|t| parse_tag(t, &tag.name).is_some());
+                    if looks_like_version(&tag.name) && !is_other_version_tag {
+                        ignored_tags.push(IgnoredTag {
+                            name: tag.name.clone(),
+                            commit_id: c.id.clone(),
+                            reason: IgnoredTagReason::TemplateMismatch {
+                                template: tag_template.clone(),
+                            },
+                        });
+                    }
                 }
             }
         }
 
-        // find how to increment the next version for unreleaed commits
-        if !is_commit_released {
-            if let Some(m) = &conv_message {
-                let commit_incr_kind = m.version_incr_kind(&cfg.version);
-                unreleased_incr_kind = max(unreleased_incr_kind, commit_incr_kind);
-            } else {
-                unreleased_incr_kind = max(unreleased_incr_kind, VersionIncr::Patch);
+        // if version tags are found, set the commit version
+        // NB: if a commit has several version tags, the highest version is selected
+        version_tags.sort_by(|(v1, t1), (v2, t2)| v2.cmp(v1).then_with(|| t1.name.cmp(&t2.name)));
+        if let Some((v, tag)) = version_tags.first() {
+            // eprintln!(" => version: {}", v);
+            latest_version_tag = Some((*tag).clone());
+            if curr_version.is_none() {
+                curr_version = Some(v.clone());
+            }
+            for (other_v, other_tag) in &version_tags[1..] {
+                // NB: a prerelease promoted to a release on the same commit is expected (eg. `v1.2.0-rc.3` and `v1.2.0`)
+                let is_promoted = !other_v.pre.is_empty()
+                    && version_tags.iter().any(|(v, _)| {
+                        v.pre.is_empty()
+                            && (v.major, v.minor, v.patch)
+                                == (other_v.major, other_v.minor, other_v.patch)
+                    });
+                if is_promoted {
+                    continue;
+                }
+                ignored_tags.push(IgnoredTag {
+                    name: other_tag.name.clone(),
+                    commit_id: c.id.clone(),
+                    reason: IgnoredTagReason::Conflict {
+                        tag: tag.name.clone(),
+                    },
+                });
+            }
+        }
+        for (v, _) in version_tags {
+            if v.pre.is_empty() && stable_version.is_none() {
+                stable_version = Some(v.clone());
+                stable_index = Some(commits.len());
             }
+            versions.push(v);
+        }
+
+        // NB: the tags are read from all the commits, but only the matching commits are kept
+        let files = c.files.unwrap_or_default();
+        if !matchers.iter().all(|m| m.is_any_match(&files)) {
+            continue;
         }
 
+        // NB: a merge commit is detected from its parents, since its message can be edited
+        let kind = if c.parent_ids.len() > 1 {
+            CommitKind::Merge
+        } else {
+            CommitKind::classify(&c.message)
+        };
+        let ignored = cfg.commit.ignored_kinds.contains(&kind);
+
         commits.push(Commit {
             id: c.id,
             date: c.date,
@@ -285,20 +714,127 @@ pub fn commit_history(cwd: &Path, cfg: &Config) -> Result<CommitHistory, Error>
             committer_email: c.committer_email,
             raw_message: c.message,
             conv_message,
-            tag,
+            parse_warnings,
+            files: if matchers.is_empty() {
+                None
+            } else {
+                Some(files)
+            },
+            kind,
+            reverts: None,
+            ignored,
+            version_incr: VersionIncr::None,
+            tags,
             version_tag: latest_version_tag.clone(),
         });
     }
 
-    let next_version = unreleased_incr_kind.apply(&curr_version);
+    // the versions released before the start of the range are found from the tags reachable from the range start
+    let range_start = match &opts.range {
+        Some(range) => gitcc_git::range_start(&repo, range)?,
+        None => None,
+    };
+    if let Some(start) = range_start {
+        let start_opts = CommitLogOptions {
+            range: Some(start),
+            first_parent: opts.first_parent,
+            order: LogOrder::Topological,
+            ..Default::default()
+        };
+        for c in gitcc_git::commit_log_until(&repo, &start_opts, is_stable_release)? {
+            for v in commit_versions(&c.id) {
+                if curr_version.is_none() {
+                    curr_version = Some(v.clone());
+                }
+                if v.pre.is_empty() && stable_version.is_none() {
+                    stable_version = Some(v.clone());
+                }
+                versions.push(v);
+            }
+        }
+    }
+
+    resolve_reverts(&mut commits);
+
+    // find how to increment the next version for the commits since the last stable release
+    // NB: the commits released in a prerelease are included
+    let stable_index = stable_index.unwrap_or(commits.len());
+    let ignore_breaking = cfg.version.zero_major == ZeroMajorPolicy::InitialDevelopment
+        && stable_version.as_ref().map_or(true, |v| v.major == 0);
+    let mut unreleased_incr_kind = VersionIncr::None;
+    for (i, c) in commits.iter_mut().enumerate().filter(|(_, c)| !c.ignored) {
+        c.version_incr = match &c.conv_message {
+            Some(m) if ignore_breaking => m.type_version_incr_kind(&cfg.version),
+            Some(m) => m.version_incr_kind(&cfg.version),
+            None => VersionIncr::Patch,
+        };
+        if i < stable_index {
+            unreleased_incr_kind = max(unreleased_incr_kind, c.version_incr);
+        }
+    }
+
+    let next_version = match unreleased_incr_kind {
+        VersionIncr::None => None,
+        incr => Some(incr.apply(&stable_version, cfg.version.zero_major)),
+    };
 
     Ok(CommitHistory {
         commits,
         curr_version,
+        stable_version,
+        versions,
         next_version,
+        tag_template,
+        ignored_tags,
     })
 }
 
+/// Resolves the revert commits to the commits they revert
+///
+/// When a revert commit and the commit it reverts are both unreleased, they cancel each other,
+/// and are both ignored.
+fn resolve_reverts(commits: &mut [Commit]) {
+    // NB: the commits are ordered with the last commit first, so a reverted commit is after its revert
+    for i in 0..commits.len() {
+        if commits[i].kind != CommitKind::Revert || commits[i].ignored {
+            continue;
+        }
+        let target = match revert_target(&commits[i].raw_message) {
+            Some(t) => t,
+            None => continue,
+        };
+        let j = commits[i + 1..]
+            .iter()
+            .position(|c| match &target {
+                RevertTarget::Id(id) => c.id.starts_with(id.as_str()),
+                RevertTarget::Subject(subject) => c.subject() == *subject,
+            })
+            .map(|j| i + 1 + j);
+        let j = match j {
+            Some(j) => j,
+            None => {
+                log::debug!(
+                    "commit {} reverts a commit which is not found",
+                    commits[i].id
+                );
+                continue;
+            }
+        };
+        commits[i].reverts = Some(commits[j].id.clone());
+
+        let is_unreleased = commits[i].version_tag.is_none() && commits[j].version_tag.is_none();
+        if is_unreleased && !commits[j].ignored {
+            log::debug!(
+                "commit {} cancels unreleased commit {}",
+                commits[i].id,
+                commits[j].id
+            );
+            commits[i].ignored = true;
+            commits[j].ignored = true;
+        }
+    }
+}
+
 /// Commits the changes to git
 pub fn commit_changes(cwd: &Path, message: &str) -> Result<gitcc_git::Commit, Error> {
     let repo = gitcc_git::discover_repo(cwd)?;
@@ -310,12 +846,13 @@ mod tests {
     use time::macros::format_description;
 
     use super::*;
+    use crate::{test_utils, PackageConfig};
 
     #[test]
     fn test_history() {
         let cwd = std::env::current_dir().unwrap();
         let cfg = Config::load_from_fs(&cwd).unwrap().unwrap_or_default();
-        let history = commit_history(&cwd, &cfg).unwrap();
+        let history = commit_history(&cwd, &cfg, None).unwrap();
         for c in &history.commits {
             eprintln!(
                 "{}: {} | {} | {} {}",
@@ -338,10 +875,17 @@ mod tests {
                     .as_ref()
                     .map(|t| t.name.to_string())
                     .unwrap_or("unreleased".to_string()),
-                if let Some(tag) = &c.tag {
-                    format!("<- {}", &tag.name)
-                } else {
+                if c.tags.is_empty() {
                     "".to_string()
+                } else {
+                    format!(
+                        "<- {}",
+                        c.tags
+                            .iter()
+                            .map(|t| t.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
                 }
             );
         }
@@ -353,7 +897,433 @@ mod tests {
                 .map(|v| v.to_string())
                 .unwrap_or("unreleased".to_string())
         );
-        eprintln!("next version: {}", history.next_version);
+        eprintln!(
+            "next version: {}",
+            history
+                .next_version
+                .map(|v| v.to_string())
+                .unwrap_or("none".to_string())
+        );
         eprintln!();
     }
+
+    #[test]
+    fn test_history_commit_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config::default();
+
+        test_utils::commit(&repo, "fix: fix a bug");
+        let feat_id = test_utils::commit(&repo, "feat: add a feature");
+        test_utils::commit(&repo, "fixup! feat: add a feature");
+        let revert_id = test_utils::commit(
+            &repo,
+            &format!("Revert \"feat: add a feature\"\n\nThis reverts commit {feat_id}.\n"),
+        );
+        test_utils::commit_with_parents(&repo, "Merge branch 'other'", &[&revert_id]);
+
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        let kinds = history.commits.iter().map(|c| c.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                CommitKind::Merge,
+                CommitKind::Revert,
+                CommitKind::Fixup,
+                CommitKind::Regular,
+                CommitKind::Regular
+            ]
+        );
+        let ignored = history
+            .commits
+            .iter()
+            .map(|c| c.ignored)
+            .collect::<Vec<_>>();
+        assert_eq!(ignored, [true, true, true, true, false]);
+        assert_eq!(
+            history.commits[1].reverts.as_deref(),
+            Some(feat_id.as_str())
+        );
+    }
+
+    #[test]
+    fn test_history_empty_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config::default();
+
+        test_utils::commit(&repo, "feat: add a feature");
+        test_utils::commit(&repo, "");
+        test_utils::commit(&repo, "Revert \"feat: add a feature\"");
+
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.commits[1].subject(), "");
+        assert_eq!(
+            history.commits[0].reverts.as_ref(),
+            Some(&history.commits[2].id)
+        );
+        assert_eq!(history.next_version, Some(Version::new(0, 1, 0)));
+    }
+
+    #[test]
+    fn test_next_prerelease() {
+        let v = Version::new(1, 3, 0);
+        let released = ["1.3.0-rc.1", "1.3.0-beta.2", "1.2.0-rc.4", "1.2.0"]
+            .map(|v| v.parse::<Version>().unwrap());
+        let next = |channel| next_prerelease(&v, channel, &released).unwrap().to_string();
+        assert_eq!(next("rc"), "1.3.0-rc.2");
+        assert_eq!(next("beta"), "1.3.0-beta.3");
+        assert_eq!(next("alpha"), "1.3.0-alpha.1");
+        assert!(next_prerelease(&v, "r c", &released).is_err());
+    }
+
+    #[test]
+    fn test_history_prerelease() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config::default();
+
+        test_utils::commit(&repo, "feat: add a feature");
+        gitcc_git::set_annotated_tag(&repo, "v1.2.0", "Release v1.2.0").unwrap();
+        test_utils::commit(&repo, "feat: add another feature");
+        gitcc_git::set_annotated_tag(&repo, "v1.3.0-rc.1", "Release v1.3.0-rc.1").unwrap();
+        test_utils::commit(&repo, "fix: fix a bug");
+
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.curr_version, Some("1.3.0-rc.1".parse().unwrap()));
+        assert_eq!(history.stable_version, Some(Version::new(1, 2, 0)));
+        // NB: the increment is computed from the last stable version
+        assert_eq!(history.next_version, Some(Version::new(1, 3, 0)));
+        assert_eq!(
+            history
+                .next_prerelease_version("rc")
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "1.3.0-rc.2"
+        );
+        assert_eq!(
+            history
+                .next_prerelease_version("beta")
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "1.3.0-beta.1"
+        );
+    }
+
+    #[test]
+    fn test_apply_strict() {
+        let policy = ZeroMajorPolicy::Strict;
+        let v = Some(Version::new(0, 3, 1));
+        assert_eq!(VersionIncr::None.apply(&v, policy), Version::new(0, 3, 1));
+        assert_eq!(VersionIncr::Patch.apply(&v, policy), Version::new(0, 3, 2));
+        assert_eq!(VersionIncr::Minor.apply(&v, policy), Version::new(0, 4, 0));
+        assert_eq!(VersionIncr::Major.apply(&v, policy), Version::new(1, 0, 0));
+        assert_eq!(
+            VersionIncr::Major.apply(&None, policy),
+            Version::new(0, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_apply_breaking_bumps_minor() {
+        let policy = ZeroMajorPolicy::BreakingBumpsMinor;
+        let v = Some(Version::new(0, 3, 1));
+        assert_eq!(VersionIncr::Patch.apply(&v, policy), Version::new(0, 3, 2));
+        assert_eq!(VersionIncr::Minor.apply(&v, policy), Version::new(0, 4, 0));
+        assert_eq!(VersionIncr::Major.apply(&v, policy), Version::new(0, 4, 0));
+        // NB: the policy only applies to 0.x versions
+        let v = Some(Version::new(1, 3, 1));
+        assert_eq!(VersionIncr::Major.apply(&v, policy), Version::new(2, 0, 0));
+    }
+
+    #[test]
+    fn test_apply_initial_development() {
+        let policy = ZeroMajorPolicy::InitialDevelopment;
+        let v = Some(Version::new(0, 3, 1));
+        assert_eq!(VersionIncr::Patch.apply(&v, policy), Version::new(0, 3, 2));
+        assert_eq!(VersionIncr::Minor.apply(&v, policy), Version::new(0, 4, 0));
+
+        // NB: breaking changes are ignored, the increment depends on the type
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let mut cfg = Config::default();
+        cfg.version.zero_major = policy;
+        test_utils::commit(&repo, "feat: add a feature");
+        gitcc_git::set_annotated_tag(&repo, "v0.3.1", "Release v0.3.1").unwrap();
+        test_utils::commit(&repo, "fix!: fix a bug");
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.commits[0].version_incr, VersionIncr::Patch);
+        assert_eq!(history.next_version, Some(Version::new(0, 3, 2)));
+
+        cfg.version.zero_major = ZeroMajorPolicy::Strict;
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.next_version, Some(Version::new(1, 0, 0)));
+    }
+
+    #[test]
+    fn test_tag_template() {
+        let v = "1.2.0-rc.1".parse::<Version>().unwrap();
+        for template in [
+            "v{{version}}",
+            "release-{{version}}",
+            "mycrate@{{version}}-final",
+        ] {
+            let tag = format_tag(template, &v);
+            assert_eq!(parse_tag(template, &tag), Some(v.clone()));
+        }
+        assert_eq!(format_tag("mycrate@{{version}}", &v), "mycrate@1.2.0-rc.1");
+        assert_eq!(parse_tag("v{{version}}", "1.2.0"), None);
+        assert_eq!(parse_tag("v{{version}}", "vnext"), None);
+        assert_eq!(parse_tag("mycrate@{{version}}", "other@1.2.0"), None);
+    }
+
+    #[test]
+    fn test_history_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config {
+            packages: vec![PackageConfig {
+                name: "foo".to_string(),
+                paths: vec!["crates/foo/**".to_string()],
+                tag_template: "{{package}}-v{{version}}".to_string(),
+                changelog: None,
+                bump_files: vec![],
+            }],
+            ..Default::default()
+        };
+
+        test_utils::commit_file(&repo, "crates/foo/lib.rs", "1", "feat: add foo");
+        gitcc_git::set_annotated_tag(&repo, "foo-v1.0.0", "Release foo-v1.0.0").unwrap();
+        test_utils::commit_file(&repo, "crates/bar/lib.rs", "1", "feat: add bar");
+        gitcc_git::set_annotated_tag(&repo, "v2.0.0", "Release v2.0.0").unwrap();
+        test_utils::commit_file(&repo, "crates/foo/lib.rs", "2", "fix: fix foo");
+        test_utils::commit_file(&repo, "crates/bar/lib.rs", "2", "feat!: change bar");
+
+        let opts = CommitHistoryOptions {
+            package: Some("foo".to_string()),
+            ..Default::default()
+        };
+        let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+        let subjects = history
+            .commits
+            .iter()
+            .map(|c| c.subject())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["fix: fix foo", "feat: add foo"]);
+        assert_eq!(history.curr_version, Some(Version::new(1, 0, 0)));
+        assert_eq!(history.next_version_str().as_deref(), Some("foo-v1.0.1"));
+
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.commits.len(), 4);
+        assert!(history.commits[0].files.is_none());
+
+        let opts = CommitHistoryOptions {
+            paths: vec!["crates/bar/*".to_string()],
+            ..Default::default()
+        };
+        let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+        assert_eq!(history.commits.len(), 2);
+        assert_eq!(
+            history.commits[0].files.as_deref(),
+            Some(["crates/bar/lib.rs".to_string()].as_slice())
+        );
+        assert_eq!(history.next_version_str().as_deref(), Some("v3.0.0"));
+
+        let opts = CommitHistoryOptions {
+            package: Some("baz".to_string()),
+            ..Default::default()
+        };
+        assert!(commit_history(dir.path(), &cfg, Some(opts)).is_err());
+    }
+
+    #[test]
+    fn test_history_since_last_release_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config::default();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = |message: &str, time: i64, parents: &[git2::Oid]| {
+            let sig = git2::Signature::new("gitcc", "gitcc@example.com", &git2::Time::new(time, 0))
+                .unwrap();
+            let parents = parents
+                .iter()
+                .map(|id| repo.find_commit(*id).unwrap())
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(None, &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+
+        // NB: the commit of the merged branch is older than the release commit
+        let c0 = commit("feat: add a feature", 1_000, &[]);
+        let c1 = commit("fix: fix a bug", 3_000, &[c0]);
+        let b1 = commit("feat: add another feature", 2_000, &[c0]);
+        let sig = repo.signature().unwrap();
+        repo.tag(
+            "v1.0.0",
+            &repo.find_object(c1, None).unwrap(),
+            &sig,
+            "Release v1.0.0",
+            false,
+        )
+        .unwrap();
+
+        // NB: the branch can be merged into the release branch, or the other way around
+        for parents in [[c1, b1], [b1, c1]] {
+            let m = commit("Merge branch 'other'", 4_000, &parents);
+            repo.reference("refs/heads/main", m, true, "").unwrap();
+            repo.set_head("refs/heads/main").unwrap();
+
+            let opts = CommitHistoryOptions {
+                since_last_release: true,
+                ..Default::default()
+            };
+            let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+            let ids = history
+                .commits
+                .iter()
+                .map(|c| c.id.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, [m.to_string(), b1.to_string(), c1.to_string()]);
+            assert_eq!(history.stable_version, Some(Version::new(1, 0, 0)));
+            assert_eq!(history.next_version, Some(Version::new(1, 1, 0)));
+        }
+    }
+
+    #[test]
+    fn test_history_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config::default();
+
+        test_utils::commit(&repo, "feat: add a feature");
+        gitcc_git::set_annotated_tag(&repo, "v1.0.0", "Release v1.0.0").unwrap();
+        test_utils::commit(&repo, "fix: fix a bug");
+        gitcc_git::set_annotated_tag(&repo, "v1.0.1", "Release v1.0.1").unwrap();
+        test_utils::commit(&repo, "feat: add another feature");
+
+        let opts = CommitHistoryOptions {
+            since_last_release: true,
+            ..Default::default()
+        };
+        let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+        assert_eq!(history.commits.len(), 2);
+        assert_eq!(history.curr_version, Some(Version::new(1, 0, 1)));
+        assert_eq!(history.next_version, Some(Version::new(1, 1, 0)));
+
+        let opts = CommitHistoryOptions {
+            range: Some("v1.0.0..v1.0.1".to_string()),
+            ..Default::default()
+        };
+        let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+        assert_eq!(history.commits.len(), 1);
+        assert_eq!(history.commits[0].subject(), "fix: fix a bug");
+        assert_eq!(
+            history.commits[0]
+                .version_tag
+                .as_ref()
+                .map(|t| t.name.as_str()),
+            Some("v1.0.1")
+        );
+    }
+
+    #[test]
+    fn test_history_lightweight_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+
+        test_utils::commit(&repo, "feat: add a feature");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1.0.0", head.as_object(), false)
+            .unwrap();
+        test_utils::commit(&repo, "fix: fix a bug");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("release-1.0.1", head.as_object(), false)
+            .unwrap();
+
+        let mut cfg = Config::default();
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.curr_version, None);
+        let ignored = history
+            .ignored_tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.reason.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ignored,
+            [
+                (
+                    "release-1.0.1",
+                    IgnoredTagReason::TemplateMismatch {
+                        template: "v{{version}}".to_string()
+                    }
+                ),
+                ("v1.0.0", IgnoredTagReason::Lightweight),
+            ]
+        );
+
+        cfg.version.lightweight_tags = true;
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.curr_version, Some(Version::new(1, 0, 0)));
+        assert_eq!(history.next_version, Some(Version::new(1, 0, 1)));
+        assert_eq!(history.ignored_tags.len(), 1);
+    }
+
+    #[test]
+    fn test_history_multiple_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let cfg = Config {
+            packages: vec![PackageConfig {
+                name: "foo".to_string(),
+                paths: vec!["crates/foo/**".to_string()],
+                tag_template: "{{package}}-v{{version}}".to_string(),
+                changelog: None,
+                bump_files: vec![],
+            }],
+            ..Default::default()
+        };
+
+        test_utils::commit_file(&repo, "crates/foo/lib.rs", "1", "feat: add foo");
+        for tag in ["v1.2.0", "v1.2.0-rc.3", "v1.1.9", "foo-v0.1.0"] {
+            gitcc_git::set_annotated_tag(&repo, tag, &format!("Release {tag}")).unwrap();
+        }
+        test_utils::commit_file(&repo, "crates/foo/lib.rs", "2", "fix: fix foo");
+
+        let history = commit_history(dir.path(), &cfg, None).unwrap();
+        assert_eq!(history.curr_version, Some(Version::new(1, 2, 0)));
+        assert_eq!(history.next_version_str().as_deref(), Some("v1.2.1"));
+        let tagged = &history.commits[1];
+        assert_eq!(tagged.tags.len(), 4);
+        assert_eq!(tagged.version_tag.as_ref().unwrap().name, "v1.2.0");
+        // NB: the promoted prerelease and the package tag are not reported
+        let ignored = history
+            .ignored_tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.reason.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ignored,
+            [(
+                "v1.1.9",
+                IgnoredTagReason::Conflict {
+                    tag: "v1.2.0".to_string()
+                }
+            )]
+        );
+
+        let opts = CommitHistoryOptions {
+            package: Some("foo".to_string()),
+            since_last_release: true,
+            ..Default::default()
+        };
+        let history = commit_history(dir.path(), &cfg, Some(opts)).unwrap();
+        assert_eq!(history.curr_version, Some(Version::new(0, 1, 0)));
+        assert_eq!(history.next_version_str().as_deref(), Some("foo-v0.1.1"));
+        assert!(history.ignored_tags.is_empty());
+    }
 }
@@ -5,10 +5,13 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use gitcc_convco::LintRules;
 use gitcc_git::discover_repo;
 use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, ChangelogConfig, CommitConfig, ReleaseConfig, VersioningConfig};
+use crate::{
+    error::Error, ChangelogConfig, CommitConfig, PackageConfig, ReleaseConfig, VersioningConfig,
+};
 
 /// Config directory name
 pub const CONFIG_DIR_NAME: &str = ".gitcc";
@@ -21,12 +24,18 @@ pub const CONFIG_FILE_NAME: &str = "config.toml";
 pub struct Config {
     /// Commit configuration
     pub commit: CommitConfig,
+    /// Lint configuration
+    #[serde(default)]
+    pub lint: LintRules,
     /// Versioning configuration
     pub version: VersioningConfig,
     /// Changelog configuration
     pub changelog: ChangelogConfig,
     /// Release configuration
     pub release: ReleaseConfig,
+    /// Packages (monorepo)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageConfig>,
 }
 
 impl Config {
@@ -0,0 +1,372 @@
+//! Git hooks
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gitcc_git::{discover_repo, get_config};
+
+use crate::{Config, Error};
+
+/// Git hooks managed by gitcc
+pub const MANAGED_HOOKS: [&str; 2] = ["commit-msg", "prepare-commit-msg"];
+
+/// Marker identifying a hook installed by gitcc
+const HOOK_MARKER: &str = "# gitcc-managed-hook";
+
+/// Header key for the gitcc version which installed the hook
+const HOOK_VERSION_KEY: &str = "# gitcc-version:";
+
+/// Header key for the gitcc binary called by the hook
+const HOOK_BIN_KEY: &str = "# gitcc-bin:";
+
+/// Suffix of a pre-existing hook chained by a gitcc hook
+const CHAINED_HOOK_SUFFIX: &str = ".pre-gitcc";
+
+/// Version of gitcc
+const GITCC_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Status of a git hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookStatus {
+    /// No hook
+    NotInstalled,
+    /// Hook installed by gitcc, and up to date
+    Installed,
+    /// Hook installed by gitcc, but by another version or another binary
+    Stale {
+        /// gitcc version which installed the hook
+        version: String,
+        /// gitcc binary called by the hook
+        bin: String,
+    },
+    /// Hook not managed by gitcc
+    Foreign,
+}
+
+impl Display for HookStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookStatus::NotInstalled => write!(f, "not installed"),
+            HookStatus::Installed => write!(f, "installed"),
+            HookStatus::Stale { version, bin } => {
+                write!(f, "stale (installed by gitcc {version} at {bin})")
+            }
+            HookStatus::Foreign => write!(f, "not managed by gitcc"),
+        }
+    }
+}
+
+/// Info about a git hook
+#[derive(Debug, Clone)]
+pub struct HookInfo {
+    /// Hook name (eg. `commit-msg`)
+    pub name: String,
+    /// Hook file
+    pub path: PathBuf,
+    /// Status
+    pub status: HookStatus,
+    /// Indicates that a pre-existing hook is chained
+    pub chains: bool,
+}
+
+/// Returns the hooks directory of the repo
+///
+/// The `core.hooksPath` git config is taken into account, and defaults to `.git/hooks`.
+pub fn hooks_dir(cwd: &Path) -> Result<PathBuf, Error> {
+    let repo = discover_repo(cwd)?;
+    let cfg = get_config(&repo)?;
+    // NB: git config keys are case insensitive, and normalized by git2
+    let hooks_path = cfg
+        .get("core.hookspath")
+        .or_else(|| cfg.get("core.hooksPath"));
+    let dir = match hooks_path {
+        Some(p) => {
+            let p = PathBuf::from(p);
+            if p.is_absolute() {
+                p
+            } else {
+                repo.workdir().unwrap_or_else(|| repo.path()).join(p)
+            }
+        }
+        None => repo.path().join("hooks"),
+    };
+    Ok(dir)
+}
+
+/// Returns the status of the hooks managed by gitcc
+///
+/// The `bin` is the path to the current gitcc binary, used to detect stale hooks.
+pub fn hooks_status(cwd: &Path, bin: &Path) -> Result<Vec<HookInfo>, Error> {
+    let dir = hooks_dir(cwd)?;
+    let mut infos = vec![];
+    for name in MANAGED_HOOKS {
+        let path = dir.join(name);
+        let status = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            hook_status(&content, bin)
+        } else {
+            HookStatus::NotInstalled
+        };
+        let chains = chained_hook_path(&path).exists();
+        infos.push(HookInfo {
+            name: name.to_string(),
+            path,
+            status,
+            chains,
+        });
+    }
+    Ok(infos)
+}
+
+/// Installs the hooks managed by gitcc
+///
+/// A pre-existing hook which is not managed by gitcc is not overwritten,
+/// it is renamed and chained by the gitcc hook.
+///
+/// The `bin` is the path to the gitcc binary called by the hooks.
+pub fn install_hooks(cwd: &Path, bin: &Path) -> Result<Vec<HookInfo>, Error> {
+    let dir = hooks_dir(cwd)?;
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+
+    for name in MANAGED_HOOKS {
+        let path = dir.join(name);
+        let chained_path = chained_hook_path(&path);
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            if hook_status(&content, bin) == HookStatus::Foreign {
+                if chained_path.exists() {
+                    return Err(Error::msg(
+                        format!(
+                            "cannot chain hook '{name}': '{}' already exists",
+                            chained_path.display()
+                        )
+                        .as_str(),
+                    ));
+                }
+                fs::rename(&path, &chained_path)?;
+            }
+        }
+        fs::write(&path, render_hook(name, bin))?;
+        set_executable(&path)?;
+    }
+
+    hooks_status(cwd, bin)
+}
+
+/// Uninstalls the hooks managed by gitcc
+///
+/// Chained hooks are restored.
+pub fn uninstall_hooks(cwd: &Path, bin: &Path) -> Result<Vec<HookInfo>, Error> {
+    let dir = hooks_dir(cwd)?;
+    for name in MANAGED_HOOKS {
+        let path = dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        if hook_status(&content, bin) == HookStatus::Foreign {
+            continue;
+        }
+        fs::remove_file(&path)?;
+        let chained_path = chained_hook_path(&path);
+        if chained_path.exists() {
+            fs::rename(&chained_path, &path)?;
+        }
+    }
+
+    hooks_status(cwd, bin)
+}
+
+/// Prepares the commit message (`prepare-commit-msg` hook)
+///
+/// If the message is not provided by another source (`-m`, template, merge, etc...),
+/// a reminder of the conventional commit format and of the valid types is added as comments.
+pub fn prepare_commit_msg(cfg: &Config, file: &Path, source: Option<&str>) -> Result<(), Error> {
+    if source.is_some() {
+        return Ok(());
+    }
+    let message = fs::read_to_string(file)?;
+    fs::write(file, insert_commit_msg_help(cfg, &message))?;
+    Ok(())
+}
+
+/// Inserts the conventional commit help before the 1st comment line of a message
+fn insert_commit_msg_help(cfg: &Config, message: &str) -> String {
+    let types = cfg.commit.types.keys().cloned().collect::<Vec<_>>();
+    let help = format!(
+        "# Conventional commit: <type>[(<scope>)][!]: <description>\n# Valid types: {}\n#\n",
+        types.join(", ")
+    );
+
+    let mut lines = message.split_inclusive('\n').collect::<Vec<_>>();
+    let i = lines
+        .iter()
+        .position(|l| l.starts_with('#'))
+        .unwrap_or(lines.len());
+    lines.insert(i, &help);
+    lines.concat()
+}
+
+/// Returns the path of the chained hook
+fn chained_hook_path(path: &Path) -> PathBuf {
+    let mut p = path.as_os_str().to_owned();
+    p.push(CHAINED_HOOK_SUFFIX);
+    PathBuf::from(p)
+}
+
+/// Returns the status of a hook from its content
+fn hook_status(content: &str, bin: &Path) -> HookStatus {
+    if !content.lines().any(|l| l == HOOK_MARKER) {
+        return HookStatus::Foreign;
+    }
+
+    let header_value = |key: &str| {
+        content
+            .lines()
+            .find_map(|l| l.strip_prefix(key))
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default()
+    };
+    let version = header_value(HOOK_VERSION_KEY);
+    let hook_bin = header_value(HOOK_BIN_KEY);
+    if version != GITCC_VERSION || Path::new(&hook_bin) != bin {
+        return HookStatus::Stale {
+            version,
+            bin: hook_bin,
+        };
+    }
+    HookStatus::Installed
+}
+
+/// Renders a hook script
+fn render_hook(name: &str, bin: &Path) -> String {
+    let bin = bin.display().to_string();
+    let bin_arg = shell_quote(&bin);
+    let cmd = match name {
+        "commit-msg" => r#"lint "$1""#,
+        _ => r#"hooks run prepare-commit-msg "$@""#,
+    };
+    format!(
+        r#"#!/bin/sh
+{HOOK_MARKER}
+{HOOK_VERSION_KEY} {GITCC_VERSION}
+{HOOK_BIN_KEY} {bin}
+#
+# This hook is managed by gitcc (run `gitcc hooks uninstall` to remove it).
+
+chained="$0{CHAINED_HOOK_SUFFIX}"
+if [ -x "$chained" ]; then
+    "$chained" "$@" || exit $?
+fi
+
+gitcc={bin_arg}
+if [ ! -x "$gitcc" ]; then
+    gitcc="gitcc"
+fi
+exec "$gitcc" {cmd}
+"#
+    )
+}
+
+/// Quotes a shell argument (eg. `it's` => `'it'\''s'`)
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Makes a file executable
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = fs::metadata(path)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+/// Makes a file executable
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_status() {
+        let bin = Path::new("/usr/bin/gitcc");
+        let hook = render_hook("commit-msg", bin);
+        assert_eq!(hook_status(&hook, bin), HookStatus::Installed);
+        assert!(matches!(
+            hook_status(&hook, Path::new("/opt/gitcc")),
+            HookStatus::Stale { .. }
+        ));
+        let old_hook = hook.replace(GITCC_VERSION, "0.0.1");
+        assert_eq!(
+            hook_status(&old_hook, bin),
+            HookStatus::Stale {
+                version: "0.0.1".to_string(),
+                bin: "/usr/bin/gitcc".to_string()
+            }
+        );
+        assert_eq!(hook_status("#!/bin/sh\nexit 0\n", bin), HookStatus::Foreign);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_hook_quotes_bin() {
+        let bin = r#"/opt/it's "$HOME" `id`/gitcc"#;
+        let hook = render_hook("commit-msg", Path::new(bin));
+        let line = hook.lines().find(|l| l.starts_with("gitcc=")).unwrap();
+        assert_eq!(line, r#"gitcc='/opt/it'\''s "$HOME" `id`/gitcc'"#);
+
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{line}\nprintf '%s' \"$gitcc\""))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), bin);
+    }
+
+    #[test]
+    fn test_install_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        gitcc_git::GitRepository::init(dir.path()).unwrap();
+        let bin = Path::new("/usr/bin/gitcc");
+
+        let hooks_dir = hooks_dir(dir.path()).unwrap();
+        fs::create_dir_all(&hooks_dir).unwrap();
+        fs::write(hooks_dir.join("commit-msg"), "#!/bin/sh\nexit 0\n").unwrap();
+
+        let infos = install_hooks(dir.path(), bin).unwrap();
+        for info in &infos {
+            assert_eq!(info.status, HookStatus::Installed);
+        }
+        assert!(infos[0].chains);
+        assert!(!infos[1].chains);
+
+        let infos = uninstall_hooks(dir.path(), bin).unwrap();
+        assert_eq!(infos[0].status, HookStatus::Foreign);
+        assert_eq!(infos[1].status, HookStatus::NotInstalled);
+        assert_eq!(
+            fs::read_to_string(hooks_dir.join("commit-msg")).unwrap(),
+            "#!/bin/sh\nexit 0\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_commit_msg_help() {
+        let cfg = Config::default();
+        let msg = "\n# Please enter the commit message for your changes.\n";
+        let msg = insert_commit_msg_help(&cfg, msg);
+        assert!(msg.starts_with("\n# Conventional commit: "));
+        assert!(msg.ends_with("#\n# Please enter the commit message for your changes.\n"));
+    }
+}
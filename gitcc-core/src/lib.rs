@@ -1,17 +1,31 @@
 //! Core functionalities
 
+mod bump;
 mod changelog;
 mod commit;
 mod config;
 mod error;
+mod hooks;
+mod lint;
+mod package;
 mod release;
+#[cfg(test)]
+mod test_utils;
 
+pub use bump::*;
 pub use changelog::*;
 pub use commit::*;
 pub use config::*;
 pub use error::*;
+pub use hooks::*;
+pub use lint::*;
+pub use package::*;
 pub use release::*;
 
 pub use gitcc_changelog::TEMPLATE_CHANGELOG_STD;
-pub use gitcc_convco::{ConvcoMessage, StringExt};
+pub use gitcc_convco::{
+    CommitKind, ConvcoError, ConvcoErrorKind, ConvcoMessage, LintIssue, LintRules, Rule, Severity,
+    StringExt,
+};
+pub use semver;
 pub use time;
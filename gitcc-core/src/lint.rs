@@ -0,0 +1,207 @@
+//! Lint
+
+use std::path::Path;
+
+use gitcc_convco::{CommitKind, ConvcoError, ConvcoMessage, LintIssue, Severity};
+use gitcc_git::discover_repo;
+
+use crate::{Config, Error};
+
+/// Scissors line used by `git commit --verbose`
+///
+/// Everything below this line is removed by git from the commit message.
+const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+
+/// Lint report of a commit message
+#[derive(Debug)]
+pub struct LintReport {
+    /// Commit ID (None if the message is not from a commit)
+    pub commit_id: Option<String>,
+    /// Linted message
+    pub message: String,
+    /// Parsed convco message (None if not a conventional message)
+    pub conv_message: Option<ConvcoMessage>,
+    /// Parse error (None if the message is a conventional message)
+    pub parse_error: Option<ConvcoError>,
+    /// Issues (errors and warnings)
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    /// Checks if the message is valid (no errors)
+    pub fn is_valid(&self) -> bool {
+        !self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+}
+
+/// Lints a commit message
+///
+/// The message is cleaned up the way git does (comments and lines below the scissors line are removed),
+/// so that the raw message file passed to a `commit-msg` hook can be linted directly.
+///
+/// The message is checked against the `[lint]` rules. If the `type_enum` rule has no value,
+/// the valid types are the configured commit types.
+///
+/// The messages of the ignored kinds of commits (`commit.ignored_kinds`, eg. `fixup! ...`) are not checked.
+pub fn lint_message(cfg: &Config, message: &str) -> LintReport {
+    let message = clean_message(message);
+    let kind = CommitKind::classify(&message);
+    lint_message_of_kind(cfg, message, kind)
+}
+
+/// Lints a cleaned up commit message of a specific kind
+fn lint_message_of_kind(cfg: &Config, message: String, kind: CommitKind) -> LintReport {
+    if cfg.commit.ignored_kinds.contains(&kind) {
+        return LintReport {
+            commit_id: None,
+            message,
+            conv_message: None,
+            parse_error: None,
+            issues: vec![],
+        };
+    }
+
+    let mut issues = vec![];
+    let mut parse_error = None;
+    let conv_message = match ConvcoMessage::parse_with(&message, &cfg.commit.parse_options()) {
+        Ok(m) => {
+            let mut rules = cfg.lint.clone();
+            if rules.type_enum.value.is_empty() {
+                rules.type_enum.value = cfg.commit.types.keys().cloned().collect();
+            }
+            issues.extend(rules.check(&m));
+            Some(m)
+        }
+        Err(err) => {
+            issues.push(LintIssue {
+                rule: "parse",
+                severity: Severity::Error,
+                message: err.to_string(),
+            });
+            parse_error = Some(err);
+            None
+        }
+    };
+
+    LintReport {
+        commit_id: None,
+        message,
+        conv_message,
+        parse_error,
+        issues,
+    }
+}
+
+/// Lints the commits of a revision range (eg. `main..HEAD`)
+///
+/// The list is ordered with the last commit first.
+/// The ignored kinds of commits (`commit.ignored_kinds`, eg. merge commits) are skipped.
+pub fn lint_commits(cwd: &Path, cfg: &Config, range: &str) -> Result<Vec<LintReport>, Error> {
+    let repo = discover_repo(cwd)?;
+    let commits = gitcc_git::commit_log_range(&repo, range)?;
+    let reports = commits
+        .into_iter()
+        .filter_map(|c| {
+            let message = clean_message(&c.message);
+            // NB: a merge commit is detected from its parents, since its message can be edited
+            let kind = if c.parent_ids.len() > 1 {
+                CommitKind::Merge
+            } else {
+                CommitKind::classify(&message)
+            };
+            if cfg.commit.ignored_kinds.contains(&kind) {
+                return None;
+            }
+            let mut report = lint_message_of_kind(cfg, message, kind);
+            report.commit_id = Some(c.id);
+            Some(report)
+        })
+        .collect();
+    Ok(reports)
+}
+
+/// Cleans up a commit message
+///
+/// Comment lines and everything below the scissors line are removed,
+/// and leading and trailing blank lines are trimmed.
+pub fn clean_message(message: &str) -> String {
+    let mut lines = vec![];
+    for line in message.lines() {
+        if line == SCISSORS_LINE {
+            break;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        lines.push(line.trim_end());
+    }
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use gitcc_convco::Rule;
+
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn test_clean_message() {
+        let raw = "feat: add lint command
+
+# Please enter the commit message for your changes.
+# On branch main
+Some body
+# ------------------------ >8 ------------------------
+diff --git a/file b/file
+";
+        assert_eq!(clean_message(raw), "feat: add lint command\n\nSome body");
+    }
+
+    #[test]
+    fn test_lint_message() {
+        let cfg = Config::default();
+        assert!(lint_message(&cfg, "feat: add lint command\n").is_valid());
+        assert!(!lint_message(&cfg, "added lint command").is_valid());
+        assert!(!lint_message(&cfg, "feature: add lint command").is_valid());
+        assert!(!lint_message(&cfg, "# only a comment\n").is_valid());
+        assert!(!lint_message(&cfg, "feat: Add lint command").is_valid());
+        assert!(lint_message(&cfg, "feat(core,cli/lint): add lint command").is_valid());
+    }
+
+    #[test]
+    fn test_lint_message_rules() {
+        let mut cfg = Config::default();
+        cfg.lint.desc_case = Severity::Warn;
+        cfg.lint.header_max_length = Rule::new(Severity::Error, 20);
+
+        let report = lint_message(&cfg, "feat: Add lint command");
+        assert_eq!(report.issues.len(), 2);
+        assert_eq!(report.issues[0].rule, "desc_case");
+        assert_eq!(report.issues[0].severity, Severity::Warn);
+        assert_eq!(report.issues[1].rule, "header_max_length");
+        assert!(!report.is_valid());
+
+        cfg.lint.header_max_length.level = Severity::Warn;
+        assert!(lint_message(&cfg, "feat: Add lint command").is_valid());
+    }
+
+    #[test]
+    fn test_lint_ignored_kinds() {
+        let cfg = Config::default();
+        assert!(lint_message(&cfg, "fixup! feat: add lint command").is_valid());
+        assert!(lint_message(&cfg, "squash! feat: add lint command").is_valid());
+        assert!(!lint_message(&cfg, "Revert \"feat: add lint command\"").is_valid());
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        let base_id = test_utils::commit(&repo, "feat: add lint command");
+        test_utils::commit(&repo, "fixup! feat: add lint command");
+        test_utils::commit_with_parents(&repo, "Merged other", &[&base_id]);
+        test_utils::commit(&repo, "fix: fix lint command");
+
+        let reports = lint_commits(dir.path(), &cfg, &format!("{base_id}..HEAD")).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].message, "fix: fix lint command");
+    }
+}
@@ -0,0 +1,159 @@
+//! Packages (monorepo)
+
+use std::path::PathBuf;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{BumpFile, Config, Error};
+
+/// Placeholder of the package name in a tag template
+const TAG_PACKAGE_PLACEHOLDER: &str = "{{package}}";
+
+/// Special characters of a glob
+pub(crate) const GLOB_CHARS: [char; 4] = ['*', '?', '[', '{'];
+
+/// Package configuration
+///
+/// In a monorepo, each package has its own versions, tags and changelog.
+/// A commit belongs to a package if it changes a file matching the package paths.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageConfig {
+    /// Name
+    pub name: String,
+    /// Path globs, relative to the repo root (eg. `crates/foo/**`)
+    pub paths: Vec<String>,
+    /// Template of the version tags (eg. `{{package}}-v{{version}}`)
+    #[serde(default = "default_package_tag_template")]
+    pub tag_template: String,
+    /// Changelog file, relative to the repo root
+    ///
+    /// By default, the changelog is in the base directory of the 1st path glob (eg. `crates/foo/CHANGELOG.md`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<PathBuf>,
+    /// Version files, relative to the repo root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bump_files: Vec<BumpFile>,
+}
+
+/// Default tag template of a package
+fn default_package_tag_template() -> String {
+    format!("{TAG_PACKAGE_PLACEHOLDER}-v{{{{version}}}}")
+}
+
+impl PackageConfig {
+    /// Returns the tag template, with the package name
+    pub fn tag_template(&self) -> String {
+        self.tag_template
+            .replace(TAG_PACKAGE_PLACEHOLDER, &self.name)
+    }
+
+    /// Returns the changelog file, relative to the repo root
+    pub fn changelog_path(&self) -> PathBuf {
+        if let Some(p) = &self.changelog {
+            return p.clone();
+        }
+        let base_dir = self
+            .paths
+            .first()
+            .map(|p| {
+                p.split('/')
+                    .take_while(|c| !c.contains(GLOB_CHARS))
+                    .collect::<PathBuf>()
+            })
+            .unwrap_or_default();
+        base_dir.join("CHANGELOG.md")
+    }
+
+    /// Returns a matcher for the package paths
+    pub fn matcher(&self) -> Result<PackageMatcher, Error> {
+        PackageMatcher::new(&self.paths).map_err(|err| {
+            Error::msg(format!("invalid paths for package '{}': {err}", self.name).as_str())
+        })
+    }
+}
+
+/// Matcher of the files belonging to a package
+#[derive(Debug, Clone)]
+pub struct PackageMatcher {
+    globs: GlobSet,
+}
+
+impl PackageMatcher {
+    /// Creates a matcher from path globs, relative to the repo root (eg. `crates/foo/**`)
+    ///
+    /// A path without glob characters matches a file, or all the files of a directory.
+    pub fn new<S: AsRef<str>>(paths: &[S]) -> Result<Self, Error> {
+        let mut builder = GlobSetBuilder::new();
+        for p in paths {
+            let p = p.as_ref().trim_end_matches('/');
+            let glob = Glob::new(p).map_err(|err| Error::msg(&err.to_string()))?;
+            builder.add(glob);
+            if !p.contains(GLOB_CHARS) {
+                let glob =
+                    Glob::new(&format!("{p}/**")).map_err(|err| Error::msg(&err.to_string()))?;
+                builder.add(glob);
+            }
+        }
+        let globs = builder
+            .build()
+            .map_err(|err| Error::msg(&err.to_string()))?;
+        Ok(Self { globs })
+    }
+
+    /// Checks if a file belongs to the package (path relative to the repo root)
+    pub fn is_match(&self, file: &str) -> bool {
+        self.globs.is_match(file)
+    }
+
+    /// Checks if any of the files belongs to the package
+    pub fn is_any_match<S: AsRef<str>>(&self, files: &[S]) -> bool {
+        files.iter().any(|f| self.is_match(f.as_ref()))
+    }
+}
+
+impl Config {
+    /// Returns a package configuration
+    pub fn package(&self, name: &str) -> Result<&PackageConfig, Error> {
+        self.packages
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| Error::msg(format!("package '{name}' not found in the config").as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(paths: &[&str]) -> PackageConfig {
+        PackageConfig {
+            name: "foo".to_string(),
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            tag_template: default_package_tag_template(),
+            changelog: None,
+            bump_files: vec![],
+        }
+    }
+
+    #[test]
+    fn test_package_config() {
+        let pkg = package(&["crates/foo/**", "shared/*.toml"]);
+        assert_eq!(pkg.tag_template(), "foo-v{{version}}");
+        assert_eq!(
+            pkg.changelog_path(),
+            PathBuf::from("crates/foo/CHANGELOG.md")
+        );
+
+        let matcher = pkg.matcher().unwrap();
+        assert!(matcher.is_match("crates/foo/src/lib.rs"));
+        assert!(matcher.is_match("shared/deps.toml"));
+        assert!(!matcher.is_match("crates/bar/src/lib.rs"));
+        assert!(matcher.is_any_match(&["README.md", "crates/foo/Cargo.toml"]));
+
+        let matcher = PackageMatcher::new(&["crates/bar/", "README.md"]).unwrap();
+        assert!(matcher.is_match("crates/bar/src/lib.rs"));
+        assert!(matcher.is_match("README.md"));
+        assert!(!matcher.is_match("crates/barbaz/lib.rs"));
+    }
+}
@@ -1,43 +1,587 @@
 //! Release
 
-use std::{path::Path, process::Command};
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
 
-use gitcc_git::discover_repo;
+pub use gitcc_git::PushProgress;
+use gitcc_git::{discover_repo, StatusShow};
 use itertools::Itertools;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use crate::Error;
+use crate::{BumpFile, Error, FileBump, VersionIncr};
+
+/// Default timeout of a release command (seconds)
+const DEFAULT_TIMEOUT: u64 = 300;
 
 /// Release configuration
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseConfig {
     /// Bump commands
     ///
-    /// The version is passed as a tag `{{version}}`
-    pub bump_cmds: Vec<String>,
+    /// A command is either a command line (split with the shell quoting rules), an array of arguments,
+    /// or a table with `args` or `shell` (run with `sh -c`), and an optional `timeout`.
+    ///
+    /// The version is passed as `{{version}}` (eg. `1.2.0`), and the tag name as `{{tag}}` (eg. `v1.2.0`).
+    ///
+    /// NB: `{{version}}` used to be the tag name (eg. `v1.2.0`), `{{tag}}` must be used instead.
+    /// The commands are run from the repo root, with the [ReleaseEnv] environment variables.
+    pub bump_cmds: Vec<ReleaseCommand>,
+    /// Default timeout of the bump commands and hooks (seconds)
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// Version files, bumped in place (eg. `Cargo.toml`, `package.json`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bump_files: Vec<BumpFile>,
+    /// Hooks, run at each stage of the release (same format as the bump commands)
+    #[serde(default, skip_serializing_if = "ReleaseHooks::is_empty")]
+    pub hooks: ReleaseHooks,
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        Self {
+            bump_cmds: vec![],
+            timeout: default_timeout(),
+            bump_files: vec![],
+            hooks: ReleaseHooks::default(),
+        }
+    }
+}
+
+/// Default timeout of the release commands
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT
+}
+
+/// Release stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseStage {
+    /// Before anything is modified (eg. run the tests)
+    PreRelease,
+    /// After the changelog is written
+    PostChangelog,
+    /// Before the release commit, after the versions are bumped (eg. regenerate the lock files)
+    PreCommit,
+    /// After the release tag is set (eg. publish the packages)
+    PostTag,
+    /// After the changes are pushed
+    PostPush,
+}
+
+impl Display for ReleaseStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseStage::PreRelease => write!(f, "pre_release"),
+            ReleaseStage::PostChangelog => write!(f, "post_changelog"),
+            ReleaseStage::PreCommit => write!(f, "pre_commit"),
+            ReleaseStage::PostTag => write!(f, "post_tag"),
+            ReleaseStage::PostPush => write!(f, "post_push"),
+        }
+    }
+}
+
+/// Release hooks (`[release.hooks]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseHooks {
+    /// Commands run before anything is modified
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_release: Vec<ReleaseCommand>,
+    /// Commands run after the changelog is written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_changelog: Vec<ReleaseCommand>,
+    /// Commands run before the release commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_commit: Vec<ReleaseCommand>,
+    /// Commands run after the release tag is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_tag: Vec<ReleaseCommand>,
+    /// Commands run after the changes are pushed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_push: Vec<ReleaseCommand>,
+}
+
+impl ReleaseHooks {
+    /// Checks if there is no hook
+    pub fn is_empty(&self) -> bool {
+        self.pre_release.is_empty()
+            && self.post_changelog.is_empty()
+            && self.pre_commit.is_empty()
+            && self.post_tag.is_empty()
+            && self.post_push.is_empty()
+    }
+
+    /// Returns the commands of a stage
+    pub fn commands(&self, stage: ReleaseStage) -> &[ReleaseCommand] {
+        match stage {
+            ReleaseStage::PreRelease => &self.pre_release,
+            ReleaseStage::PostChangelog => &self.post_changelog,
+            ReleaseStage::PreCommit => &self.pre_commit,
+            ReleaseStage::PostTag => &self.post_tag,
+            ReleaseStage::PostPush => &self.post_push,
+        }
+    }
+}
+
+/// Release command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReleaseCommand {
+    /// Command line (eg. `cargo set-version "{{version}}"`)
+    ///
+    /// The line is split with the shell quoting rules, and can start with environment variables (eg. `FOO=1 make`).
+    /// Pipes and other shell operators require the shell form.
+    Line(String),
+    /// Program and arguments (eg. `["cargo", "set-version", "{{version}}"]`)
+    Args(Vec<String>),
+    /// Command with options
+    Detailed(ReleaseCommandConfig),
+}
+
+/// Release command with options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseCommandConfig {
+    /// Program and arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    /// Shell script (run with `sh -c`, or `cmd /C` on Windows)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// Timeout (seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+impl Display for ReleaseCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseCommand::Line(line) => write!(f, "{line}"),
+            ReleaseCommand::Args(args) => write!(f, "{}", shell_words::join(args)),
+            ReleaseCommand::Detailed(ReleaseCommandConfig { shell: Some(s), .. }) => {
+                write!(f, "{s}")
+            }
+            ReleaseCommand::Detailed(ReleaseCommandConfig { args, .. }) => {
+                write!(f, "{}", shell_words::join(args.iter().flatten()))
+            }
+        }
+    }
+}
+
+/// Release environment, passed to the release commands
+#[derive(Debug, Clone)]
+pub struct ReleaseEnv {
+    /// Next version (`GITCC_VERSION`, `{{version}}`)
+    pub version: Version,
+    /// Next version tag (`GITCC_TAG`, `{{tag}}`)
+    pub tag: String,
+    /// Previous version (`GITCC_PREV_VERSION`, empty if none)
+    pub prev_version: Option<Version>,
+    /// Version increment (`GITCC_INCREMENT`: `major`, `minor`, `patch`, or `na`)
+    pub increment: VersionIncr,
+}
+
+impl ReleaseEnv {
+    /// Returns the environment variables
+    pub fn vars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("GITCC_VERSION", self.version.to_string()),
+            ("GITCC_TAG", self.tag.clone()),
+            (
+                "GITCC_PREV_VERSION",
+                self.prev_version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("GITCC_INCREMENT", self.increment.to_string()),
+        ]
+    }
+
+    /// Replaces the placeholders (`{{version}}`, `{{tag}}`)
+    fn replace_placeholders(&self, s: &str) -> String {
+        s.replace("{{version}}", &self.version.to_string())
+            .replace("{{tag}}", &self.tag)
+    }
 }
 
-/// Executes a bump command
-pub fn exec_bump_command(cmd: &str, version: &str) -> Result<(), Error> {
-    let cmd = cmd.replace("{{version}}", version);
-    let cmd_split = cmd.split(' ').collect::<Vec<_>>();
-    let program = cmd_split[0];
-    let args = cmd_split[1..].iter().copied().collect_vec();
+/// Executes a release command (bump command or hook)
+///
+/// The command is run from the repo root, and its output is streamed to the current process output.
+/// The command is killed if it does not complete before the timeout (the command timeout, or the default timeout).
+pub fn exec_release_command(
+    cmd: &ReleaseCommand,
+    env: &ReleaseEnv,
+    root_dir: &Path,
+    default_timeout: Duration,
+) -> Result<(), Error> {
+    let mut cmd_env = vec![];
+    let (mut process, timeout) = match cmd {
+        ReleaseCommand::Line(line) => {
+            let words = shell_words::split(line)
+                .map_err(|err| Error::msg(format!("invalid command '{line}': {err}").as_str()))?;
+            // NB: the leading variable assignments are set in the command environment (eg. `FOO=1 make`)
+            let mut words = words.into_iter().peekable();
+            while let Some((k, v)) = words.peek().and_then(|w| parse_env_assignment(w)) {
+                cmd_env.push((k, v));
+                words.next();
+            }
+            (build_process(&words.collect_vec(), env)?, None)
+        }
+        ReleaseCommand::Args(args) => (build_process(args, env)?, None),
+        ReleaseCommand::Detailed(cfg) => {
+            let process = match (&cfg.args, &cfg.shell) {
+                (Some(args), None) => build_process(args, env)?,
+                (None, Some(script)) => build_shell_process(&env.replace_placeholders(script)),
+                _ => return Err(Error::msg("a command must have either 'args' or 'shell'")),
+            };
+            (process, cfg.timeout.map(Duration::from_secs))
+        }
+    };
+    let timeout = timeout.unwrap_or(default_timeout);
 
-    let cmd_res = Command::new(program)
-        .args(&args)
-        .output()
+    let mut child = process
+        .current_dir(root_dir)
+        .envs(env.vars())
+        .envs(cmd_env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
         .map_err(|err| Error::msg(format!("failed to execute '{cmd}': {err}").as_str()))?;
 
-    if !cmd_res.status.success() {
-        let stderr = String::from_utf8_lossy(&cmd_res.stderr);
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Err(Error::msg(
+                format!("command '{cmd}' timed out after {}s", timeout.as_secs_f32()).as_str(),
+            ));
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    if !status.success() {
         return Err(Error::msg(
-            format!("failed to execute '{cmd}': {stderr}").as_str(),
+            format!("command '{cmd}' failed ({status})").as_str(),
         ));
     }
     Ok(())
 }
 
+/// Builds a process from a program and its arguments
+fn build_process<S: AsRef<str>>(args: &[S], env: &ReleaseEnv) -> Result<Command, Error> {
+    let Some((program, args)) = args.split_first() else {
+        return Err(Error::msg("empty command"));
+    };
+    let mut process = Command::new(env.replace_placeholders(program.as_ref()));
+    process.args(args.iter().map(|a| env.replace_placeholders(a.as_ref())));
+    Ok(process)
+}
+
+/// Builds a shell process
+fn build_shell_process(script: &str) -> Command {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut process = Command::new(shell);
+    process.arg(flag).arg(script);
+    process
+}
+
+/// Parses an environment variable assignment (eg. `FOO=1`)
+fn parse_env_assignment(word: &str) -> Option<(String, String)> {
+    let (key, value) = word.split_once('=')?;
+    let is_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_key.then(|| (key.to_string(), value.to_string()))
+}
+
+/// Release plan, executed in a [ReleaseTransaction]
+#[derive(Debug, Clone)]
+pub struct ReleasePlan {
+    /// Release environment
+    pub env: ReleaseEnv,
+    /// Changelog file, relative to the repo root
+    pub changelog_path: PathBuf,
+    /// Changelog content
+    pub changelog: String,
+    /// Version file bumps
+    pub bumps: Vec<FileBump>,
+    /// Bump commands
+    pub bump_cmds: Vec<ReleaseCommand>,
+    /// Hooks
+    pub hooks: ReleaseHooks,
+    /// Default timeout of the commands
+    pub timeout: Duration,
+    /// Message of the release commit
+    pub commit_message: String,
+    /// Message of the release tag
+    pub tag_message: String,
+}
+
+/// Release step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseStep {
+    /// A file was written (path relative to the repo root)
+    WriteFile(PathBuf),
+    /// A bump command was executed
+    BumpCommand(ReleaseCommand),
+    /// A hook was executed
+    Hook(ReleaseStage, ReleaseCommand),
+    /// The release commit was created (commit ID)
+    Commit(String),
+    /// The release tag was set
+    Tag(String),
+    /// The release was pushed (remote name)
+    Push(String),
+}
+
+impl Display for ReleaseStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseStep::WriteFile(path) => write!(f, "wrote {}", path.display()),
+            ReleaseStep::BumpCommand(cmd) => write!(f, "executed bump command: {cmd}"),
+            ReleaseStep::Hook(stage, cmd) => write!(f, "executed {stage} hook: {cmd}"),
+            ReleaseStep::Commit(id) => write!(f, "committed changes ({id})"),
+            ReleaseStep::Tag(tag) => write!(f, "tag {tag} added"),
+            ReleaseStep::Push(remote) => write!(f, "pushed the release to {remote}"),
+        }
+    }
+}
+
+/// Release transaction
+///
+/// The transaction records the release steps, so that a failed release can be rolled back.
+#[derive(Debug)]
+pub struct ReleaseTransaction {
+    /// Repo root directory
+    root_dir: PathBuf,
+    /// HEAD commit before the release
+    head_id: String,
+    /// Files dirty before the release
+    dirty_files: BTreeSet<String>,
+    /// Original content of the written files (None if the file did not exist)
+    backups: Vec<(PathBuf, Option<Vec<u8>>)>,
+    /// Steps performed
+    steps: Vec<ReleaseStep>,
+}
+
+impl ReleaseTransaction {
+    /// Begins a release transaction
+    pub fn begin(root_dir: &Path) -> Result<Self, Error> {
+        let repo = discover_repo(root_dir)?;
+        let head_id = gitcc_git::head_commit_id(&repo)?;
+        let dirty_files = gitcc_git::repo_status(&repo, StatusShow::IndexAndWorkdir)?
+            .into_keys()
+            .collect();
+        Ok(Self {
+            root_dir: root_dir.to_path_buf(),
+            head_id,
+            dirty_files,
+            backups: vec![],
+            steps: vec![],
+        })
+    }
+
+    /// Returns the steps performed
+    pub fn steps(&self) -> &[ReleaseStep] {
+        &self.steps
+    }
+
+    /// Executes a release plan
+    ///
+    /// The callback is called after each step.
+    /// On failure, the steps performed so far are left as is, and can be rolled back with [ReleaseTransaction::rollback].
+    pub fn execute<F>(&mut self, plan: &ReleasePlan, mut on_step: F) -> Result<(), Error>
+    where
+        F: FnMut(&ReleaseStep),
+    {
+        self.run_hooks(plan, ReleaseStage::PreRelease, &mut on_step)?;
+
+        self.write_file(&plan.changelog_path, plan.changelog.as_bytes())?;
+        on_step(self.last_step());
+        self.run_hooks(plan, ReleaseStage::PostChangelog, &mut on_step)?;
+
+        for bump in plan.bumps.iter().filter(|b| b.is_changed()) {
+            self.write_file(&bump.path, bump.new_content.as_bytes())?;
+            on_step(self.last_step());
+        }
+        for cmd in &plan.bump_cmds {
+            exec_release_command(cmd, &plan.env, &self.root_dir, plan.timeout)?;
+            self.steps.push(ReleaseStep::BumpCommand(cmd.clone()));
+            on_step(self.last_step());
+        }
+
+        self.run_hooks(plan, ReleaseStage::PreCommit, &mut on_step)?;
+        self.commit(&plan.commit_message)?;
+        on_step(self.last_step());
+
+        self.tag(&plan.env.tag, &plan.tag_message)?;
+        on_step(self.last_step());
+        self.run_hooks(plan, ReleaseStage::PostTag, &mut on_step)?;
+
+        Ok(())
+    }
+
+    /// Pushes the release branch and tag to a remote, and runs the `post_push` hooks
+    ///
+    /// The callbacks are called after each step, and on the push progress.
+    /// Once pushed, the release cannot be rolled back.
+    pub fn push<F, P>(
+        &mut self,
+        plan: &ReleasePlan,
+        remote: &str,
+        mut on_step: F,
+        on_progress: P,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&ReleaseStep),
+        P: FnMut(PushProgress),
+    {
+        let repo = discover_repo(&self.root_dir)?;
+        let mut refspecs = vec![gitcc_git::head_branch_ref(&repo)?];
+        for step in &self.steps {
+            if let ReleaseStep::Tag(tag) = step {
+                refspecs.push(format!("refs/tags/{tag}"));
+            }
+        }
+        gitcc_git::push_to_remote(&repo, remote, &refspecs, on_progress)
+            .map_err(|err| Error::msg(format!("failed to push to {remote}: {err}").as_str()))?;
+        self.steps.push(ReleaseStep::Push(remote.to_string()));
+        on_step(self.last_step());
+
+        self.run_hooks(plan, ReleaseStage::PostPush, &mut on_step)
+    }
+
+    /// Checks if the release was pushed
+    pub fn is_pushed(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| matches!(step, ReleaseStep::Push(_)))
+    }
+
+    /// Returns the last step
+    fn last_step(&self) -> &ReleaseStep {
+        self.steps.last().expect("no release step")
+    }
+
+    /// Runs the hooks of a stage
+    fn run_hooks<F>(
+        &mut self,
+        plan: &ReleasePlan,
+        stage: ReleaseStage,
+        on_step: &mut F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&ReleaseStep),
+    {
+        for cmd in plan.hooks.commands(stage) {
+            exec_release_command(cmd, &plan.env, &self.root_dir, plan.timeout)
+                .map_err(|err| Error::msg(format!("{stage} hook failed: {err}").as_str()))?;
+            self.steps.push(ReleaseStep::Hook(stage, cmd.clone()));
+            on_step(self.last_step());
+        }
+        Ok(())
+    }
+
+    /// Writes a file (path relative to the repo root)
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<(), Error> {
+        let full_path = self.root_dir.join(path);
+        if !self.backups.iter().any(|(p, _)| p == path) {
+            self.backups
+                .push((path.to_path_buf(), fs::read(&full_path).ok()));
+        }
+        if let Some(dir) = full_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&full_path, content).map_err(|err| {
+            Error::msg(format!("failed to write {}: {err}", path.display()).as_str())
+        })?;
+        self.steps.push(ReleaseStep::WriteFile(path.to_path_buf()));
+        Ok(())
+    }
+
+    /// Commits all the changes
+    fn commit(&mut self, message: &str) -> Result<(), Error> {
+        let repo = discover_repo(&self.root_dir)?;
+        gitcc_git::add_all(&repo)?;
+        let commit = gitcc_git::commit_to_head(&repo, message)
+            .map_err(|err| Error::msg(format!("failed to commit: {err}").as_str()))?;
+        self.steps.push(ReleaseStep::Commit(commit.id));
+        Ok(())
+    }
+
+    /// Sets an annotated tag to the HEAD
+    fn tag(&mut self, tag: &str, message: &str) -> Result<(), Error> {
+        let repo = discover_repo(&self.root_dir)?;
+        gitcc_git::set_annotated_tag(&repo, tag, message)
+            .map_err(|err| Error::msg(format!("failed to add tag {tag}: {err}").as_str()))?;
+        self.steps.push(ReleaseStep::Tag(tag.to_string()));
+        Ok(())
+    }
+
+    /// Rolls back the release
+    ///
+    /// The release tag is deleted, the HEAD and the index are reset to the commit before the release,
+    /// the written files are restored, and the other files changed by the commands are restored from the HEAD
+    /// (or deleted if they are new).
+    ///
+    /// NB: the files which were dirty before the release are left as is (except the written files),
+    /// and the changes which were staged before the release are unstaged.
+    /// A pushed release cannot be rolled back.
+    pub fn rollback(self) -> Result<(), Error> {
+        if self.is_pushed() {
+            return Err(Error::msg(
+                "the release is pushed, and cannot be rolled back",
+            ));
+        }
+        let repo = discover_repo(&self.root_dir)?;
+        for step in self.steps.iter().rev() {
+            if let ReleaseStep::Tag(tag) = step {
+                gitcc_git::delete_tag(&repo, tag)?;
+            }
+        }
+        gitcc_git::reset_mixed(&repo, &self.head_id)?;
+
+        let is_written = |path: &str| self.backups.iter().any(|(p, _)| p == Path::new(path));
+        for (path, status) in gitcc_git::repo_status(&repo, StatusShow::IndexAndWorkdir)? {
+            if self.dirty_files.contains(&path) || is_written(&path) {
+                continue;
+            }
+            let full_path = self.root_dir.join(&path);
+            if status.is_wt_new() && path.ends_with('/') {
+                fs::remove_dir_all(full_path)?;
+            } else if status.is_wt_new() {
+                fs::remove_file(full_path)?;
+            } else {
+                gitcc_git::restore_file(&repo, &path)?;
+            }
+        }
+
+        for (path, content) in &self.backups {
+            let full_path = self.root_dir.join(path);
+            match content {
+                Some(content) => fs::write(full_path, content)?,
+                None if full_path.exists() => fs::remove_file(full_path)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Add all changes to the index
 pub fn add_all_changes(cwd: &Path) -> Result<(), Error> {
     let repo = discover_repo(cwd)?;
@@ -50,8 +594,264 @@ pub fn set_annotated_tag(cwd: &Path, tag: &str, message: &str) -> Result<(), Err
     Ok(gitcc_git::set_annotated_tag(&repo, tag, message)?)
 }
 
-/// Push with tags
-pub fn push_with_tags(cwd: &Path) -> Result<(), Error> {
+/// Pushes the HEAD branch and the release tag to `origin`
+pub fn push_with_tags(cwd: &Path, tag: &str) -> Result<(), Error> {
     let repo = discover_repo(cwd)?;
-    Ok(gitcc_git::push_to_remote(&repo, "origin")?)
+    let refspecs = vec![
+        gitcc_git::head_branch_ref(&repo)?,
+        format!("refs/tags/{tag}"),
+    ];
+    Ok(gitcc_git::push_to_remote(
+        &repo,
+        "origin",
+        &refspecs,
+        |_| {},
+    )?)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    fn release_env() -> ReleaseEnv {
+        ReleaseEnv {
+            version: Version::new(1, 3, 0),
+            tag: "v1.3.0".to_string(),
+            prev_version: Some(Version::new(1, 2, 4)),
+            increment: VersionIncr::Minor,
+        }
+    }
+
+    #[test]
+    fn test_replace_placeholders() {
+        let env = release_env();
+        // NB: `{{version}}` used to be replaced by the tag name
+        assert_eq!(
+            env.replace_placeholders("cargo set-version {{version}}"),
+            "cargo set-version 1.3.0"
+        );
+        assert_eq!(
+            env.replace_placeholders("git push origin {{tag}}"),
+            "git push origin v1.3.0"
+        );
+    }
+
+    #[test]
+    fn test_exec_release_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let timeout = Duration::from_secs(10);
+        let exec =
+            |cmd: ReleaseCommand| exec_release_command(&cmd, &release_env(), dir.path(), timeout);
+
+        exec(ReleaseCommand::Line(
+            r#"OUT=line.txt sh -c 'echo "$1 $GITCC_TAG" > $OUT' _ "{{version}} (next)""#
+                .to_string(),
+        ))
+        .unwrap();
+        let out = fs::read_to_string(dir.path().join("line.txt")).unwrap();
+        assert_eq!(out, "1.3.0 (next) v1.3.0\n");
+
+        exec(ReleaseCommand::Args(vec![
+            "touch".to_string(),
+            "file {{tag}}.txt".to_string(),
+        ]))
+        .unwrap();
+        assert!(dir.path().join("file v1.3.0.txt").exists());
+
+        exec(ReleaseCommand::Detailed(ReleaseCommandConfig {
+            args: None,
+            shell: Some(
+                "echo $GITCC_PREV_VERSION $GITCC_INCREMENT | tr a-z A-Z > shell.txt && echo {{version}} >> shell.txt"
+                    .to_string(),
+            ),
+            timeout: None,
+        }))
+        .unwrap();
+        let out = fs::read_to_string(dir.path().join("shell.txt")).unwrap();
+        assert_eq!(out, "1.2.4 MINOR\n1.3.0\n");
+
+        let err = exec(ReleaseCommand::Line("false".to_string())).unwrap_err();
+        assert!(err.to_string().contains("failed"));
+        let err = exec(ReleaseCommand::Detailed(ReleaseCommandConfig {
+            args: Some(vec!["sleep".to_string(), "5".to_string()]),
+            shell: None,
+            timeout: Some(0),
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn test_release_command_config() {
+        let cfg = toml::from_str::<ReleaseConfig>(
+            r#"
+bump_cmds = [
+    "cargo set-version {{version}}",
+    ["npm", "version", "{{version}}"],
+    { shell = "make bump | tee bump.log", timeout = 60 },
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.timeout, DEFAULT_TIMEOUT);
+        assert!(cfg.hooks.is_empty());
+        assert_eq!(
+            cfg.bump_cmds[1],
+            ReleaseCommand::Args(vec![
+                "npm".to_string(),
+                "version".to_string(),
+                "{{version}}".to_string()
+            ])
+        );
+        assert_eq!(cfg.bump_cmds[2].to_string(), "make bump | tee bump.log");
+
+        let cfg = toml::from_str::<ReleaseConfig>(
+            r#"
+bump_cmds = []
+
+[hooks]
+pre_release = ["cargo test"]
+post_tag = [["cargo", "publish"], { shell = "echo $GITCC_TAG", timeout = 10 }]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.hooks.commands(ReleaseStage::PreRelease),
+            [ReleaseCommand::Line("cargo test".to_string())]
+        );
+        assert!(cfg.hooks.commands(ReleaseStage::PreCommit).is_empty());
+        assert_eq!(cfg.hooks.commands(ReleaseStage::PostTag).len(), 2);
+    }
+
+    #[test]
+    fn test_release_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        test_utils::commit_file(&repo, "README.md", "readme", "feat: add readme");
+        let head_id = test_utils::commit_file(&repo, "version.txt", "1.2.4", "fix: fix a bug");
+        fs::write(dir.path().join("dirty.txt"), "dirty").unwrap();
+
+        let plan = ReleasePlan {
+            env: release_env(),
+            changelog_path: PathBuf::from("CHANGELOG.md"),
+            changelog: "# Changelog".to_string(),
+            bumps: vec![FileBump {
+                path: PathBuf::from("version.txt"),
+                old_content: "1.2.4".to_string(),
+                new_content: "1.3.0".to_string(),
+            }],
+            bump_cmds: vec![ReleaseCommand::Detailed(ReleaseCommandConfig {
+                args: None,
+                shell: Some("echo new > new.txt && echo changed > README.md".to_string()),
+                timeout: None,
+            })],
+            hooks: ReleaseHooks {
+                post_tag: vec![ReleaseCommand::Line("false".to_string())],
+                ..Default::default()
+            },
+            timeout: Duration::from_secs(10),
+            commit_message: "chore(release): Release v1.3.0".to_string(),
+            tag_message: "Release v1.3.0".to_string(),
+        };
+        let mut tx = ReleaseTransaction::begin(dir.path()).unwrap();
+        let mut steps = vec![];
+        let err = tx
+            .execute(&plan, |step| steps.push(step.to_string()))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("post_tag hook failed"));
+        assert_eq!(steps.len(), 5);
+        assert_eq!(tx.steps()[4], ReleaseStep::Tag("v1.3.0".to_string()));
+        assert_eq!(gitcc_git::list_tags(&repo).unwrap(), ["v1.3.0"]);
+
+        tx.rollback().unwrap();
+        assert_eq!(gitcc_git::head_commit_id(&repo).unwrap(), head_id);
+        assert!(gitcc_git::list_tags(&repo).unwrap().is_empty());
+        let read = |path: &str| fs::read_to_string(dir.path().join(path)).ok();
+        assert_eq!(read("version.txt").as_deref(), Some("1.2.4"));
+        assert_eq!(read("README.md").as_deref(), Some("readme"));
+        assert_eq!(read("CHANGELOG.md"), None);
+        assert_eq!(read("new.txt"), None);
+        assert_eq!(read("dirty.txt").as_deref(), Some("dirty"));
+    }
+
+    #[test]
+    fn test_release_push() {
+        let dir = tempfile::tempdir().unwrap();
+        let remote_dir = tempfile::tempdir().unwrap();
+        let remote = gitcc_git::GitRepository::init_bare(remote_dir.path()).unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        repo.remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+        test_utils::commit_file(&repo, "README.md", "readme", "feat: add readme");
+
+        let plan = ReleasePlan {
+            env: release_env(),
+            changelog_path: PathBuf::from("CHANGELOG.md"),
+            changelog: "# Changelog".to_string(),
+            bumps: vec![],
+            bump_cmds: vec![],
+            hooks: ReleaseHooks {
+                post_push: vec![ReleaseCommand::Line("touch pushed.txt".to_string())],
+                ..Default::default()
+            },
+            timeout: Duration::from_secs(10),
+            commit_message: "chore(release): Release v1.3.0".to_string(),
+            tag_message: "Release v1.3.0".to_string(),
+        };
+        let mut tx = ReleaseTransaction::begin(dir.path()).unwrap();
+        tx.execute(&plan, |_| {}).unwrap();
+        let mut steps = vec![];
+        tx.push(&plan, "origin", |step| steps.push(step.clone()), |_| {})
+            .unwrap();
+        assert_eq!(steps[0], ReleaseStep::Push("origin".to_string()));
+        assert!(matches!(
+            steps[1],
+            ReleaseStep::Hook(ReleaseStage::PostPush, _)
+        ));
+        assert!(dir.path().join("pushed.txt").exists());
+
+        let head_id = gitcc_git::head_commit_id(&repo).unwrap();
+        let branch = gitcc_git::head_branch_ref(&repo).unwrap();
+        let remote_head = remote.find_reference(&branch).unwrap();
+        assert_eq!(remote_head.target().unwrap().to_string(), head_id);
+        let remote_tag = remote.find_reference("refs/tags/v1.3.0").unwrap();
+        assert_eq!(
+            remote_tag.peel_to_commit().unwrap().id().to_string(),
+            head_id
+        );
+
+        assert!(tx.rollback().is_err());
+    }
+
+    #[test]
+    fn test_push_with_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let remote_dir = tempfile::tempdir().unwrap();
+        let remote = gitcc_git::GitRepository::init_bare(remote_dir.path()).unwrap();
+        let repo = test_utils::init_repo(dir.path());
+        repo.remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+        test_utils::commit_file(&repo, "README.md", "readme", "feat: add readme");
+        add_all_changes(dir.path()).unwrap();
+        repo.tag_lightweight(
+            "local",
+            &repo.head().unwrap().peel(git2::ObjectType::Commit).unwrap(),
+            false,
+        )
+        .unwrap();
+        set_annotated_tag(dir.path(), "v0.1.0", "Release v0.1.0").unwrap();
+
+        push_with_tags(dir.path(), "v0.1.0").unwrap();
+        let head_id = gitcc_git::head_commit_id(&repo).unwrap();
+        let branch = gitcc_git::head_branch_ref(&repo).unwrap();
+        let remote_head = remote.find_reference(&branch).unwrap();
+        assert_eq!(remote_head.target().unwrap().to_string(), head_id);
+        let remote_tag = remote.find_reference("refs/tags/v0.1.0").unwrap();
+        assert_eq!(
+            remote_tag.peel_to_commit().unwrap().id().to_string(),
+            head_id
+        );
+        assert!(remote.find_reference("refs/tags/local").is_err());
+    }
 }
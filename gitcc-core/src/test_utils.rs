@@ -0,0 +1,49 @@
+//! Test utilities
+
+use std::{fs, path::Path};
+
+use gitcc_git::GitRepository;
+
+/// Initializes a repo with a user identity
+pub fn init_repo(dir: &Path) -> GitRepository {
+    let repo = GitRepository::init(dir).unwrap();
+    let mut cfg = repo.config().unwrap();
+    cfg.set_str("user.name", "gitcc").unwrap();
+    cfg.set_str("user.email", "gitcc@example.com").unwrap();
+    repo
+}
+
+/// Commits the index to HEAD, and returns the commit ID
+pub fn commit(repo: &GitRepository, message: &str) -> String {
+    commit_with_parents(repo, message, &[])
+}
+
+/// Writes a file, adds it to the index, and commits it
+pub fn commit_file(repo: &GitRepository, path: &str, content: &str, message: &str) -> String {
+    let file = repo.workdir().unwrap().join(path);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(&file, content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    commit(repo, message)
+}
+
+/// Commits the index to HEAD with additional parents (eg. a merge commit), and returns the commit ID
+pub fn commit_with_parents(repo: &GitRepository, message: &str, other_parents: &[&str]) -> String {
+    let sig = repo.signature().unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let mut parents = vec![];
+    if let Ok(head) = repo.head() {
+        parents.push(head.peel_to_commit().unwrap());
+    }
+    for id in other_parents {
+        parents.push(repo.find_commit(id.parse().unwrap()).unwrap());
+    }
+    let parents = parents.iter().collect::<Vec<_>>();
+    let id = repo
+        .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap();
+    id.to_string()
+}
@@ -1,5 +1,7 @@
 //! Commit
 
+use std::path::Path;
+
 use git2::StatusOptions;
 use time::OffsetDateTime;
 
@@ -22,15 +24,16 @@ pub struct Commit {
     pub committer_email: String,
     /// Message
     pub message: String,
+    /// Parent IDs (several parents for a merge commit)
+    pub parent_ids: Vec<String>,
+    /// Changed files (None if not requested, see [CommitLogOptions::with_files])
+    pub files: Option<Vec<String>>,
 }
 
 impl Commit {
-    /// Returns the commit subject (1st line)
+    /// Returns the commit subject (1st line, empty if the message is empty)
     pub fn subject(&self) -> String {
-        if let Some(line) = self.message.lines().next() {
-            return line.to_string();
-        }
-        unreachable!()
+        self.message.lines().next().unwrap_or_default().to_string()
     }
 }
 
@@ -65,18 +68,157 @@ impl<'repo> TryFrom<&'repo git2::Commit<'repo>> for Commit {
                 .message()
                 .ok_or(Error::msg("non UTF8 message"))?
                 .to_string(),
+            parent_ids: c.parent_ids().map(|id| id.to_string()).collect(),
+            files: None,
         })
     }
 }
 
+/// Commit log options
+#[derive(Debug, Clone, Default)]
+pub struct CommitLogOptions {
+    /// Path filters (git pathspecs, eg. `crates/foo` or `*.md`)
+    ///
+    /// If set, only the commits changing a file matching a pathspec are returned.
+    pub paths: Vec<String>,
+    /// Lists the files changed by each commit
+    pub with_files: bool,
+    /// Revision range
+    ///
+    /// The range is either a git range (eg. `v1.0.0..HEAD`), or a single revision (eg. `HEAD~3`),
+    /// in which case all the commits reachable from that revision are returned.
+    /// By default, all the commits reachable from HEAD are returned.
+    pub range: Option<String>,
+    /// Only follows the 1st parent of merge commits
+    pub first_parent: bool,
+    /// Order of the commits
+    pub order: LogOrder,
+    /// Revisions whose reachable commits are excluded (eg. the last release commit)
+    pub hide: Vec<String>,
+}
+
+/// Order of the commit log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogOrder {
+    /// Default git order (reverse chronological, without guarantees)
+    #[default]
+    Default,
+    /// Topological order: a commit is never shown before its children
+    Topological,
+    /// Commit time order (last commit first)
+    Time,
+}
+
+impl From<LogOrder> for git2::Sort {
+    fn from(value: LogOrder) -> Self {
+        match value {
+            LogOrder::Default => git2::Sort::NONE,
+            LogOrder::Topological => git2::Sort::TOPOLOGICAL,
+            LogOrder::Time => git2::Sort::TIME,
+        }
+    }
+}
+
 /// Returns the complete commit history
 ///
 /// The returned list is ordered with the last commit first (revwalk order).
 pub fn commit_log(repo: &GitRepository) -> Result<Vec<Commit>, Error> {
+    commit_log_with(repo, &CommitLogOptions::default())
+}
+
+/// Returns the commit history with options
+///
+/// The returned list is ordered with the last commit first (revwalk order).
+pub fn commit_log_with(
+    repo: &GitRepository,
+    opts: &CommitLogOptions,
+) -> Result<Vec<Commit>, Error> {
+    commit_log_until(repo, opts, |_| false)
+}
+
+/// Returns the commit history with options, until a specific commit
+///
+/// The walk stops after the 1st commit for which `is_last` returns true (this commit is included),
+/// which avoids walking the whole history when only the recent commits are needed.
+///
+/// The returned list is ordered with the last commit first (revwalk order).
+pub fn commit_log_until<F>(
+    repo: &GitRepository,
+    opts: &CommitLogOptions,
+    is_last: F,
+) -> Result<Vec<Commit>, Error>
+where
+    F: FnMut(&Commit) -> bool,
+{
     // NB: an explanation can be found here (for Go)
     // https://stackoverflow.com/questions/37289674/how-to-run-git-log-commands-using-libgit2-in-go
     let mut revwalk = repo.revwalk()?;
-    revwalk.push_head()?;
+    match &opts.range {
+        Some(range) if range.contains("..") => {
+            revwalk.push_range(range)?;
+        }
+        Some(rev) => {
+            let obj = repo.revparse_single(rev)?;
+            revwalk.push(obj.peel_to_commit()?.id())?;
+        }
+        None => {
+            revwalk.push_head()?;
+        }
+    }
+    for rev in &opts.hide {
+        let obj = repo.revparse_single(rev)?;
+        revwalk.hide(obj.peel_to_commit()?.id())?;
+    }
+    revwalk.set_sorting(opts.order.into())?;
+    if opts.first_parent {
+        revwalk.simplify_first_parent()?;
+    }
+    walk_commits(repo, revwalk, opts, is_last)
+}
+
+/// Returns the commits of a revision range
+///
+/// The range is either a git range (eg. `main..HEAD`), or a single revision (eg. `HEAD~3`),
+/// in which case all the commits reachable from that revision are returned.
+///
+/// The returned list is ordered with the last commit first (revwalk order).
+pub fn commit_log_range(repo: &GitRepository, range: &str) -> Result<Vec<Commit>, Error> {
+    let opts = CommitLogOptions {
+        range: Some(range.to_string()),
+        ..Default::default()
+    };
+    commit_log_with(repo, &opts)
+}
+
+/// Returns the start commit of a `from..to` revision range (eg. the commit of `v1.0.0` for `v1.0.0..HEAD`)
+///
+/// The start commit is excluded from the range. None is returned if the range is a single revision.
+pub fn range_start(repo: &GitRepository, range: &str) -> Result<Option<String>, Error> {
+    if !range.contains("..") || range.contains("...") {
+        return Ok(None);
+    }
+    let spec = repo.revparse(range)?;
+    match spec.from() {
+        Some(obj) => Ok(Some(obj.peel_to_commit()?.id().to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Collects the commits of a revwalk
+fn walk_commits<F>(
+    repo: &GitRepository,
+    revwalk: git2::Revwalk,
+    opts: &CommitLogOptions,
+    mut is_last: F,
+) -> Result<Vec<Commit>, Error>
+where
+    F: FnMut(&Commit) -> bool,
+{
+    let pathspec = if opts.paths.is_empty() {
+        None
+    } else {
+        Some(git2::Pathspec::new(&opts.paths)?)
+    };
 
     // NB: revwalk starts with the last commit first
     let mut commits: Vec<_> = vec![];
@@ -88,7 +230,26 @@ pub fn commit_log(repo: &GitRepository) -> Result<Vec<Commit>, Error> {
                 if let Some(c) = obj.as_commit() {
                     // eprintln!("{:#?}", c);
                     // NB: raw text values can be invalid if not UTF8
-                    commits.push(c.try_into()?);
+                    let mut commit: Commit = c.try_into()?;
+                    let stop = is_last(&commit);
+                    let mut is_match = true;
+                    if opts.with_files || pathspec.is_some() {
+                        let files = changed_files(repo, c)?;
+                        if let Some(pathspec) = &pathspec {
+                            is_match = files.iter().any(|f| {
+                                pathspec.matches_path(Path::new(f), git2::PathspecFlags::DEFAULT)
+                            });
+                        }
+                        if opts.with_files {
+                            commit.files = Some(files);
+                        }
+                    }
+                    if is_match {
+                        commits.push(commit);
+                    }
+                    if stop {
+                        break;
+                    }
                 }
             }
             Err(err) => return Err(Error::msg(format!("{err} (revwalk)").as_str())),
@@ -127,6 +288,38 @@ pub fn commit_to_head(repo: &GitRepository, message: &str) -> Result<Commit, Err
     commit.try_into()
 }
 
+/// Returns the files changed by a commit
+///
+/// The commit is compared to its 1st parent (or to an empty tree for the root commit),
+/// and the paths are relative to the repo root. For a renamed file, both paths are returned.
+pub fn commit_changed_files(repo: &GitRepository, commit_id: &str) -> Result<Vec<String>, Error> {
+    let oid = git2::Oid::from_str(commit_id)?;
+    let commit = repo.find_commit(oid)?;
+    changed_files(repo, &commit)
+}
+
+/// Returns the files changed by a commit, compared to its 1st parent
+fn changed_files(repo: &GitRepository, commit: &git2::Commit) -> Result<Vec<String>, Error> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+    let mut files: Vec<String> = vec![];
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(path) = file.path().and_then(|p| p.to_str()) {
+                if !files.iter().any(|f| f == path) {
+                    files.push(path.to_string());
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::repo::discover_repo;
@@ -149,6 +342,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_commit_log_with_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = GitRepository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("gitcc", "gitcc@example.com").unwrap();
+        for (i, file) in ["a/lib.rs", "b/lib.rs", "a/README.md"].iter().enumerate() {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "content").unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(file)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents = match repo.head() {
+                Ok(head) => vec![head.peel_to_commit().unwrap()],
+                Err(_) => vec![],
+            };
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                &format!("commit {i}"),
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        }
+
+        let commits = commit_log(&repo).unwrap();
+        assert_eq!(commits.len(), 3);
+        assert!(commits[0].files.is_none());
+
+        let opts = CommitLogOptions {
+            paths: vec!["a".to_string()],
+            with_files: true,
+            ..Default::default()
+        };
+        let commits = commit_log_with(&repo, &opts).unwrap();
+        let messages = commits
+            .iter()
+            .map(|c| c.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["commit 2", "commit 0"]);
+        assert_eq!(commits[0].files, Some(vec!["a/README.md".to_string()]));
+
+        let opts = CommitLogOptions {
+            paths: vec!["*.rs".to_string()],
+            ..Default::default()
+        };
+        let commits = commit_log_with(&repo, &opts).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert!(commits[0].files.is_none());
+    }
+
+    #[test]
+    fn test_commit_log_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = GitRepository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("gitcc", "gitcc@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = |message: &str, parents: &[git2::Oid]| {
+            let parents = parents
+                .iter()
+                .map(|id| repo.find_commit(*id).unwrap())
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(None, &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+        let c0 = commit("c0", &[]);
+        let c1 = commit("c1", &[c0]);
+        let b1 = commit("b1", &[c0]);
+        let m = commit("merge", &[c1, b1]);
+        repo.reference("refs/heads/main", m, true, "").unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let messages = |opts: &CommitLogOptions| {
+            commit_log_with(&repo, opts)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.message)
+                .collect::<Vec<_>>()
+        };
+        let opts = CommitLogOptions {
+            order: LogOrder::Topological,
+            ..Default::default()
+        };
+        assert_eq!(messages(&opts).len(), 4);
+        let opts = CommitLogOptions {
+            first_parent: true,
+            ..Default::default()
+        };
+        assert_eq!(messages(&opts), ["merge", "c1", "c0"]);
+        let opts = CommitLogOptions {
+            range: Some(format!("{c1}..HEAD")),
+            order: LogOrder::Topological,
+            ..Default::default()
+        };
+        assert_eq!(messages(&opts), ["merge", "b1"]);
+        let opts = CommitLogOptions {
+            range: Some(c1.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(messages(&opts), ["c1", "c0"]);
+        assert_eq!(
+            range_start(&repo, &format!("{c1}..HEAD")).unwrap(),
+            Some(c1.to_string())
+        );
+        assert_eq!(range_start(&repo, &c1.to_string()).unwrap(), None);
+        let opts = CommitLogOptions {
+            hide: vec![c1.to_string()],
+            ..Default::default()
+        };
+        assert_eq!(messages(&opts), ["merge", "b1"]);
+
+        let commits = commit_log_until(&repo, &CommitLogOptions::default(), |c| {
+            c.message == "merge"
+        })
+        .unwrap();
+        assert_eq!(commits.len(), 1);
+    }
+
     #[test]
     fn test_commit_do() {
         let cwd = std::env::current_dir().unwrap();
@@ -1,6 +1,6 @@
 //! Index
 
-use git2::IndexAddOption;
+use git2::{build::CheckoutBuilder, IndexAddOption, ResetType};
 
 use crate::{Error, GitRepository};
 
@@ -11,3 +11,16 @@ pub fn add_all(repo: &GitRepository) -> Result<(), Error> {
     index.write()?;
     Ok(())
 }
+
+/// Resets the HEAD and the index to a commit, keeping the working tree (`git reset --mixed`)
+pub fn reset_mixed(repo: &GitRepository, commit_id: &str) -> Result<(), Error> {
+    let commit = repo.find_object(git2::Oid::from_str(commit_id)?, None)?;
+    Ok(repo.reset(&commit, ResetType::Mixed, None)?)
+}
+
+/// Restores a file from the HEAD, in the index and the working tree (`git restore --staged --worktree`)
+pub fn restore_file(repo: &GitRepository, path: &str) -> Result<(), Error> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().update_index(true).path(path);
+    Ok(repo.checkout_head(Some(&mut checkout))?)
+}
@@ -1,11 +1,220 @@
 //! Remote
 
+use std::{env, path::PathBuf};
+
+use git2::{Cred, CredentialType, RemoteCallbacks};
+
 use crate::{error::Error, GitRepository};
 
+/// Push progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PushProgress {
+    /// Number of objects sent
+    pub current: usize,
+    /// Total number of objects
+    pub total: usize,
+    /// Number of bytes sent
+    pub bytes: usize,
+}
+
 /// Performs a push
-pub fn push_to_remote(repo: &GitRepository, remote_name: &str) -> Result<(), Error> {
+///
+/// The refspecs are full references (eg. `refs/heads/main`, `refs/tags/v1.0.0`).
+/// If no refspecs are passed, the configured refspecs are pushed.
+///
+/// The credentials are retrieved from the SSH agent, the default SSH keys (`~/.ssh/id_ed25519`, `~/.ssh/id_rsa`),
+/// and the git credential helpers.
+pub fn push_to_remote<F>(
+    repo: &GitRepository,
+    remote_name: &str,
+    refspecs: &[String],
+    mut on_progress: F,
+) -> Result<(), Error>
+where
+    F: FnMut(PushProgress),
+{
     let mut remote = repo.find_remote(remote_name)?;
-    // NB: if no refspecs is passed, the configured refspecs are passed
-    let refspecs: [String; 0] = [];
-    Ok(remote.push::<_>(&refspecs, None)?)
+    let mut credentials = CredentialsProvider::new(repo)?;
+    let mut rejected = vec![];
+    {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks
+            .credentials(|url, username, allowed| credentials.credentials(url, username, allowed));
+        callbacks.push_transfer_progress(|current, total, bytes| {
+            on_progress(PushProgress {
+                current,
+                total,
+                bytes,
+            })
+        });
+        // NB: a reference rejected by the remote does not fail the push
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(msg) = status {
+                rejected.push(format!("{refname} ({msg})"));
+            }
+            Ok(())
+        });
+        let mut opts = git2::PushOptions::new();
+        opts.remote_callbacks(callbacks);
+        remote.push(refspecs, Some(&mut opts))?;
+    }
+    if !rejected.is_empty() {
+        return Err(Error::msg(
+            format!("push rejected: {}", rejected.join(", ")).as_str(),
+        ));
+    }
+    Ok(())
+}
+
+/// Provides the credentials to a remote
+///
+/// The remote calls the provider again if the credentials are rejected,
+/// so each source is only tried once.
+struct CredentialsProvider {
+    /// Repo config (for the credential helpers)
+    config: git2::Config,
+    /// SSH keys not tried yet
+    ssh_keys: std::vec::IntoIter<PathBuf>,
+    /// The SSH agent was tried
+    tried_agent: bool,
+    /// The credential helpers were tried
+    tried_helper: bool,
+    /// The default credentials were tried
+    tried_default: bool,
+}
+
+impl CredentialsProvider {
+    /// Creates a new provider
+    fn new(repo: &GitRepository) -> Result<Self, Error> {
+        Ok(Self {
+            config: repo.config()?,
+            ssh_keys: default_ssh_keys().into_iter(),
+            tried_agent: false,
+            tried_helper: false,
+            tried_default: false,
+        })
+    }
+
+    /// Returns the next credentials to try
+    fn credentials(
+        &mut self,
+        url: &str,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            let username = username.unwrap_or("git");
+            if !self.tried_agent {
+                self.tried_agent = true;
+                if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+                    return Ok(cred);
+                }
+            }
+            if let Some(key) = self.ssh_keys.next() {
+                return Cred::ssh_key(username, None, &key, None);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !self.tried_helper {
+            self.tried_helper = true;
+            if let Ok(cred) = Cred::credential_helper(&self.config, url, username) {
+                return Ok(cred);
+            }
+        }
+        if allowed.contains(CredentialType::DEFAULT) && !self.tried_default {
+            self.tried_default = true;
+            return Cred::default();
+        }
+        Err(git2::Error::from_str(&format!(
+            "no valid credentials found for {url}"
+        )))
+    }
+}
+
+/// Returns the default SSH keys which exist
+fn default_ssh_keys() -> Vec<PathBuf> {
+    let Some(home) = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) else {
+        return vec![];
+    };
+    let ssh_dir = PathBuf::from(home).join(".ssh");
+    ["id_ed25519", "id_rsa"]
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Initializes a repo with a commit on `main`, and a bare remote `origin`
+    fn init_repos(dir: &Path) -> (GitRepository, GitRepository) {
+        let remote = GitRepository::init_bare(dir.join("remote.git")).unwrap();
+        let repo = GitRepository::init(dir.join("repo")).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.remote("origin", dir.join("remote.git").to_str().unwrap())
+            .unwrap();
+        commit(&repo, "c0");
+        (repo, remote)
+    }
+
+    fn commit(repo: &GitRepository, message: &str) -> git2::Oid {
+        let sig = git2::Signature::now("gitcc", "gitcc@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_push_to_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let (repo, remote) = init_repos(dir.path());
+        let sig = git2::Signature::now("gitcc", "gitcc@example.com").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag("v0.1.0", head.as_object(), &sig, "Release v0.1.0", false)
+            .unwrap();
+
+        let refspecs = vec![
+            "refs/heads/main".to_string(),
+            "refs/tags/v0.1.0".to_string(),
+        ];
+        push_to_remote(&repo, "origin", &refspecs, |_| {}).unwrap();
+
+        let remote_head = remote.find_reference("refs/heads/main").unwrap();
+        assert_eq!(remote_head.target(), Some(head.id()));
+        let remote_tag = remote.find_reference("refs/tags/v0.1.0").unwrap();
+        assert_eq!(remote_tag.peel_to_commit().unwrap().id(), head.id());
+    }
+
+    #[test]
+    fn test_push_to_remote_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (repo, remote) = init_repos(dir.path());
+        let refspecs = vec!["refs/heads/main".to_string()];
+        push_to_remote(&repo, "origin", &refspecs, |_| {}).unwrap();
+
+        // rewrite the history, so that the push is not a fast-forward
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("gitcc", "gitcc@example.com").unwrap();
+        let tree = head.tree().unwrap();
+        let other_id = repo.commit(None, &sig, &sig, "c0'", &tree, &[]).unwrap();
+        repo.reference("refs/heads/main", other_id, true, "rewrite")
+            .unwrap();
+
+        assert!(push_to_remote(&repo, "origin", &refspecs, |_| {}).is_err());
+        let remote_head = remote.find_reference("refs/heads/main").unwrap();
+        assert_eq!(remote_head.target(), Some(head.id()));
+    }
 }
@@ -11,3 +11,19 @@ pub type GitRepository = git2::Repository;
 pub fn discover_repo(p: &Path) -> Result<GitRepository, Error> {
     Ok(git2::Repository::discover(p)?)
 }
+
+/// Returns the ID of the HEAD commit
+pub fn head_commit_id(repo: &GitRepository) -> Result<String, Error> {
+    Ok(repo.head()?.peel_to_commit()?.id().to_string())
+}
+
+/// Returns the full name of the branch checked out at HEAD (eg. `refs/heads/main`)
+pub fn head_branch_ref(repo: &GitRepository) -> Result<String, Error> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(Error::msg("HEAD is not on a branch"));
+    }
+    head.name()
+        .map(|name| name.to_string())
+        .ok_or(Error::msg("invalid branch name"))
+}
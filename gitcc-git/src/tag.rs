@@ -1,15 +1,17 @@
 //! Tags
 
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 use crate::{error::Error, util::convert_git2_time, GitRepository};
 
 /// A git tag
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     /// ID (hash)
     pub id: String,
-    /// Date
+    /// Date of the tagged commit
+    #[serde(with = "time::serde::rfc3339")]
     pub date: OffsetDateTime,
     /// Name (short)
     pub name: String,
@@ -19,6 +21,20 @@ pub struct Tag {
     pub message: Option<String>,
     /// Commit ID (hash)
     pub commit_id: String,
+    /// Tagger - None if lightweight tag, or if the annotated tag has no tagger
+    pub tagger: Option<Tagger>,
+}
+
+/// Tagger of an annotated tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tagger {
+    /// Name
+    pub name: String,
+    /// Email
+    pub email: String,
+    /// Date
+    #[serde(with = "time::serde::rfc3339")]
+    pub date: OffsetDateTime,
 }
 
 impl PartialEq for Tag {
@@ -32,6 +48,13 @@ impl Tag {
     pub fn is_annotated(&self) -> bool {
         self.message.is_some()
     }
+
+    /// Returns the release date
+    ///
+    /// For an annotated tag, this is the date when the tag was created, otherwise the date of the tagged commit.
+    pub fn release_date(&self) -> OffsetDateTime {
+        self.tagger.as_ref().map(|t| t.date).unwrap_or(self.date)
+    }
 }
 
 /// Retrieves all the repo tags (lightweight and annotated)
@@ -72,7 +95,17 @@ pub fn get_tag_refs(repo: &GitRepository) -> Result<Vec<Tag>, Error> {
         // peel to tag to check if the ref is a tag
         // NB: lightweight tags do not have ref of their own
         let tag = rf.peel_to_tag().ok();
-        let tag_message = tag.map(|t| t.message().unwrap_or("__invalid__").trim().to_string());
+        let tag_message = tag
+            .as_ref()
+            .map(|t| t.message().unwrap_or("__invalid__").trim().to_string());
+        let tagger = match tag.as_ref().and_then(|t| t.tagger()) {
+            Some(sig) => Some(Tagger {
+                name: sig.name().unwrap_or("__invalid__").to_string(),
+                email: sig.email().unwrap_or("__invalid__").to_string(),
+                date: convert_git2_time(sig.when())?,
+            }),
+            None => None,
+        };
 
         // peel to find the commit
         // NB: a tag always points to a commit (itself for a lightweight tag)
@@ -88,6 +121,7 @@ pub fn get_tag_refs(repo: &GitRepository) -> Result<Vec<Tag>, Error> {
                 name_full: full_name,
                 message: tag_message,
                 commit_id,
+                tagger,
             }
         })
     }
@@ -103,6 +137,11 @@ pub fn set_annotated_tag(repo: &GitRepository, tag: &str, message: &str) -> Resu
     Ok(())
 }
 
+/// Deletes a tag
+pub fn delete_tag(repo: &GitRepository, tag: &str) -> Result<(), Error> {
+    Ok(repo.tag_delete(tag)?)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::repo::discover_repo;
@@ -128,4 +167,38 @@ mod tests {
             eprintln!("{}:  {} ({})", tag.id, tag.name, tag.commit_id)
         }
     }
+
+    #[test]
+    fn test_tags_tagger() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = GitRepository::init(dir.path()).unwrap();
+        let commit_time = git2::Time::new(1_600_000_000, 0);
+        let tag_time = git2::Time::new(1_700_000_000, 60);
+        let sig = git2::Signature::new("gitcc", "gitcc@example.com", &commit_time).unwrap();
+        let tagger = git2::Signature::new("tagger", "tagger@example.com", &tag_time).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit_id = repo
+            .commit(Some("HEAD"), &sig, &sig, "c0", &tree, &[])
+            .unwrap();
+        let commit = repo.find_object(commit_id, None).unwrap();
+        repo.tag("v1.0.0", &commit, &tagger, "Release v1.0.0\n\nNotes", false)
+            .unwrap();
+        repo.tag_lightweight("v1.0.0-lw", &commit, false).unwrap();
+
+        let tags = get_tag_refs(&repo).unwrap();
+        let tag = tags.iter().find(|t| t.name == "v1.0.0").unwrap();
+        let tag_tagger = tag.tagger.as_ref().unwrap();
+        assert_eq!(tag_tagger.name, "tagger");
+        assert_eq!(tag_tagger.email, "tagger@example.com");
+        assert_eq!(tag.date.unix_timestamp(), 1_600_000_000);
+        assert_eq!(tag.release_date().unix_timestamp(), 1_700_000_000);
+        assert_eq!(tag.release_date().offset().whole_minutes(), 60);
+        assert_eq!(tag.message.as_deref(), Some("Release v1.0.0\n\nNotes"));
+
+        let tag = tags.iter().find(|t| t.name == "v1.0.0-lw").unwrap();
+        assert!(tag.tagger.is_none());
+        assert_eq!(tag.release_date().unix_timestamp(), 1_600_000_000);
+    }
 }